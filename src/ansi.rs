@@ -0,0 +1,196 @@
+// src/ansi.rs
+//
+// Minimal ANSI escape-sequence parser for the build log panel.
+//
+// Only SGR ("Select Graphic Rendition", `ESC [ … m`) sequences carry meaning
+// for a scroll-back log; they are turned into styled spans.  Every other
+// escape — cursor movement, erase-line, OSC titles/hyperlinks — is dropped so
+// it never shows up as garbage in the terminal widget.
+
+use egui::Color32;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Current text attributes.  Carried from line to line, exactly like a real
+/// terminal: a colour set on one line stays active until it is reset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnsiStyle {
+    pub fg: Option<Color32>,
+    pub bg: Option<Color32>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// Split `line` into styled text runs, updating `style` as SGR sequences are
+/// encountered.  Non-SGR escapes are removed; empty runs are omitted.
+pub fn parse_line(line: &str, style: &mut AnsiStyle) -> Vec<(String, AnsiStyle)> {
+    let mut spans = Vec::new();
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in '@'..='~'.
+            Some('[') => {
+                let mut params = String::new();
+                let mut final_byte = None;
+                for p in chars.by_ref() {
+                    if ('@'..='~').contains(&p) {
+                        final_byte = Some(p);
+                        break;
+                    }
+                    params.push(p);
+                }
+                if final_byte == Some('m') {
+                    if !text.is_empty() {
+                        spans.push((std::mem::take(&mut text), *style));
+                    }
+                    apply_sgr(&params, style);
+                }
+            }
+            // OSC: terminated by BEL or ST (`ESC \`).
+            Some(']') => {
+                while let Some(p) = chars.next() {
+                    if p == BEL {
+                        break;
+                    }
+                    if p == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-byte escapes (`ESC 7`, `ESC =`, …) or a trailing lone ESC.
+            _ => {}
+        }
+    }
+
+    if !text.is_empty() {
+        spans.push((text, *style));
+    }
+    spans
+}
+
+/// Remove every escape sequence, returning plain text.
+pub fn strip(s: &str) -> String {
+    if !s.contains(ESC) {
+        return s.to_owned();
+    }
+    let mut style = AnsiStyle::default();
+    parse_line(s, &mut style)
+        .into_iter()
+        .map(|(text, _)| text)
+        .collect()
+}
+
+// ─── SGR ──────────────────────────────────────────────────────────────────────
+
+fn apply_sgr(params: &str, style: &mut AnsiStyle) {
+    // `ESC[m` is shorthand for `ESC[0m`, and an empty param means 0.  Both
+    // ';' and ':' separate params.  Garbled or overflowing params are skipped
+    // rather than read as a reset.
+    let codes: Vec<u16> = params
+        .split([';', ':'])
+        .filter_map(|p| {
+            if p.is_empty() {
+                Some(0)
+            } else {
+                p.parse().ok()
+            }
+        })
+        .collect();
+    let mut it = codes.iter().copied();
+
+    while let Some(code) = it.next() {
+        match code {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            30..=37 => style.fg = Some(basic_color(code - 30, false)),
+            90..=97 => style.fg = Some(basic_color(code - 90, true)),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(basic_color(code - 40, false)),
+            100..=107 => style.bg = Some(basic_color(code - 100, true)),
+            49 => style.bg = None,
+            38 => style.fg = extended_color(&mut it),
+            48 => style.bg = extended_color(&mut it),
+            _ => {}
+        }
+    }
+}
+
+/// `38;5;n` (256-colour) or `38;2;r;g;b` (true colour) after the 38/48.
+fn extended_color(it: &mut impl Iterator<Item = u16>) -> Option<Color32> {
+    match it.next()? {
+        5 => Some(indexed_color(it.next()?)),
+        2 => {
+            let r = u8::try_from(it.next()?).unwrap_or(u8::MAX);
+            let g = u8::try_from(it.next()?).unwrap_or(u8::MAX);
+            let b = u8::try_from(it.next()?).unwrap_or(u8::MAX);
+            Some(Color32::from_rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// The 16 base colours, tuned to stay readable on the dark terminal panel.
+fn basic_color(n: u16, bright: bool) -> Color32 {
+    const NORMAL: [(u8, u8, u8); 8] = [
+        (102, 102, 102),
+        (255, 95, 86),
+        (13, 188, 121),
+        (229, 229, 16),
+        (59, 142, 234),
+        (188, 63, 188),
+        (17, 168, 205),
+        (229, 229, 229),
+    ];
+    const BRIGHT: [(u8, u8, u8); 8] = [
+        (128, 128, 128),
+        (241, 76, 76),
+        (35, 209, 139),
+        (245, 245, 67),
+        (90, 165, 245),
+        (214, 112, 214),
+        (41, 184, 219),
+        (255, 255, 255),
+    ];
+    let table = if bright { &BRIGHT } else { &NORMAL };
+    let (r, g, b) = table
+        .get(usize::from(n))
+        .copied()
+        .unwrap_or((229, 229, 229));
+    Color32::from_rgb(r, g, b)
+}
+
+/// xterm 256-colour palette: 16 base colours, a 6×6×6 cube, 24 greys.
+fn indexed_color(n: u16) -> Color32 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match n {
+        0..=7 => basic_color(n, false),
+        8..=15 => basic_color(n - 8, true),
+        16..=231 => {
+            let i = usize::from(n - 16);
+            Color32::from_rgb(LEVELS[i / 36], LEVELS[(i / 6) % 6], LEVELS[i % 6])
+        }
+        _ => {
+            let level = u8::try_from(8 + 10 * (n.min(255) - 232)).unwrap_or(u8::MAX);
+            Color32::from_gray(level)
+        }
+    }
+}
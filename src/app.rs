@@ -2,26 +2,51 @@
 //
 // BitForge — main application state and egui render loop.
 
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::Arc;
 
 use tokio::runtime::Runtime;
 
-use crate::compiler::{compile_bitcoin, compile_electrs};
+use crate::ansi::{self, AnsiStyle};
+use crate::compiler::{compile_bitcoin, compile_electrs, BITCOIN_CMAKE_FLAGS, ELECTRS_CARGO_FLAGS};
+use crate::config_gen::{self, Capabilities, ConfigOptions, Network};
 use crate::deps::check_dependencies_task;
-use crate::env_setup::{brew_prefix, find_brew, macos_version, setup_build_environment};
+use crate::diagnosis::{diagnose, format_report, OOM_KILL};
+use crate::env_setup::{brew_prefix, find_brew, home_dir, macos_version, setup_build_environment};
 use crate::github::{fetch_bitcoin_versions, fetch_electrs_versions};
-use crate::messages::{log_msg, AppMessage, ConfirmRequest};
-
-/// Maximum log lines retained in memory.
-const MAX_LOG_LINES: usize = 4_000;
-/// Drop to this many lines when the cap is hit.
-const TRIM_TO_LINES: usize = MAX_LOG_LINES / 2;
+use crate::history::{
+    self, format_duration, format_utc, format_utc_compact, format_utc_time, unix_now, BuildRecord,
+    BuildResult,
+};
+use crate::install::{self, InstalledProject};
+use crate::log_view::{find_matches, LineKind, LogFilter, LogIndex, LogLine};
+use crate::messages::{log_msg, AppMessage, ConfirmRequest, LogRecord, LogStream, UiSender};
+use crate::monitor::{self, format_bytes, recommend_jobs, JobAdvice, SystemStats};
+use crate::nodes::{self, NodeKind, NodeLaunch, NodeRecord};
+use crate::options::{self, LintFinding, LintLevel, OptionCatalog, OptionEntry};
+use crate::package;
+use crate::preflight::{format_checklist, has_failures, run_checks, BuildPlan};
+use crate::regtest::{self, ValidationPlan};
+use crate::signing;
+use crate::storage::{self, plan_cleanup, Cleanup, CleanupPlan, EntryKind, StorageEntry};
+use crate::support_bundle::{export_bundle, BundleSettings};
+use crate::systemd::{self, UnitSettings};
+
+/// Maximum log lines retained in memory; the oldest are dropped first.
+/// Only the rows scrolled into view are laid out, so this can be generous.
+const MAX_LOG_LINES: usize = 100_000;
+/// Monospace size of the build log terminal.
+const TERMINAL_FONT_SIZE: f32 = 11.5;
 /// Fixed pixel height for the build log terminal panel.
 const TERMINAL_HEIGHT: f32 = 260.0;
 /// Max width for the centred content column.
 const CONTENT_WIDTH: f32 = 860.0;
+/// Lines of node output kept per node terminal tab.
+const MAX_NODE_LINES: usize = 20_000;
 
 // ─── Colour palette (macOS light mode) ───────────────────────────────────────
 
@@ -42,12 +67,13 @@ mod pal {
     pub const TERM_BG: Color32 = Color32::from_rgb(18, 18, 18);
     pub const TERM_TEXT: Color32 = Color32::from_rgb(0, 215, 0);
     pub const TERM_BORDER: Color32 = Color32::from_rgb(55, 55, 55);
-}
-
-// ─── Home directory ───────────────────────────────────────────────────────────
-
-fn home_dir() -> Option<PathBuf> {
-    std::env::var_os("HOME").map(PathBuf::from)
+    pub const TERM_ERROR: Color32 = Color32::from_rgb(255, 95, 86);
+    pub const TERM_WARNING: Color32 = Color32::from_rgb(255, 204, 0);
+    pub const TERM_MATCH_BG: Color32 = Color32::from_rgb(90, 80, 20);
+    pub const TERM_MATCH_CURRENT_BG: Color32 = Color32::from_rgb(200, 120, 0);
+    pub const TERM_STDERR: Color32 = Color32::from_rgb(215, 165, 80);
+    pub const TERM_COMMAND: Color32 = Color32::from_rgb(120, 190, 255);
+    pub const TERM_TIMESTAMP: Color32 = Color32::from_rgb(110, 110, 110);
 }
 
 // ─── Modal ────────────────────────────────────────────────────────────────────
@@ -70,6 +96,53 @@ enum ModalAction {
     Confirm(bool),
 }
 
+// ─── Node consoles ─────────────────────────────────────────────────────────────
+
+/// Nodes-window settings and terminal tab for one of `bitcoind` / `electrs`.
+struct NodeConsole {
+    /// Built version to start, e.g. `29.0`.
+    version: String,
+    /// Optional config file.
+    config: String,
+    /// bitcoind's datadir, or electrs' DB dir.
+    datadir: String,
+    running: Option<NodeRecord>,
+    /// Start was clicked and neither `NodeStarted` nor `NodeStartFailed` has
+    /// arrived yet.
+    starting: bool,
+    /// Tails `running`'s log until it exits.
+    watcher: Option<tokio::task::AbortHandle>,
+    lines: VecDeque<String>,
+    /// Output after the last newline.
+    partial: String,
+}
+
+impl NodeConsole {
+    fn new(datadir: String) -> Self {
+        Self {
+            version: String::new(),
+            config: String::new(),
+            datadir,
+            running: None,
+            starting: false,
+            watcher: None,
+            lines: VecDeque::new(),
+            partial: String::new(),
+        }
+    }
+
+    fn push_output(&mut self, text: &str) {
+        self.partial.push_str(text);
+        while let Some(pos) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=pos).collect();
+            self.lines.push_back(line.trim_end().to_owned());
+        }
+        while self.lines.len() > MAX_NODE_LINES {
+            self.lines.pop_front();
+        }
+    }
+}
+
 // ─── App state ────────────────────────────────────────────────────────────────
 
 pub struct BitForgeApp {
@@ -77,6 +150,12 @@ pub struct BitForgeApp {
     target: String,
     cores: usize,
     max_cores: usize,
+    /// Memory-aware job count for the selected target.
+    job_advice: JobAdvice,
+    /// Retry a build that was killed for lack of memory at half the jobs.
+    retry_oom: bool,
+    /// gpg key that signs each build's SHA256SUMS; empty skips signing.
+    signing_key: String,
     build_dir: String,
 
     // Version lists
@@ -86,20 +165,101 @@ pub struct BitForgeApp {
     selected_electrs: String,
 
     // UI state
-    log_buffer: String,
-    log_line_count: usize,
+    log_lines: VecDeque<LogLine>,
+    /// Visible rows and search matches.  New lines are indexed as they
+    /// arrive; the whole index is rebuilt only when `log_index_dirty`.
+    log_index: LogIndex,
+    log_index_dirty: bool,
+    /// Untrimmed on-disk copy of the current build's log.
+    log_file: Option<BufWriter<File>>,
+    /// Most recent build log file, for the "Open log file" action.
+    last_log_file: Option<PathBuf>,
+    log_search: String,
+    log_filter: LogFilter,
+    /// Index of the highlighted match within the current search results.
+    log_match_index: usize,
+    /// Set by next / previous so the terminal scrolls to the match once.
+    log_scroll_to_match: bool,
+    log_show_timestamps: bool,
+    /// Commands whose output is folded under their `$ cmd` header.
+    collapsed_commands: HashSet<u64>,
     progress: f32,
     is_busy: bool,
     status_bar: String,
+    /// Live system load, shown in the status bar while a build runs.
+    system_stats: Option<SystemStats>,
+
+    // Build history
+    history: Vec<BuildRecord>,
+    show_history: bool,
+    history_target_filter: String,
+    history_result_filter: Option<BuildResult>,
+
+    // Config generator
+    show_config: bool,
+    /// `None` while the selected binaries are being probed.
+    config_caps: Option<Capabilities>,
+    config_network: Network,
+    config_datadir: String,
+    config_prune: bool,
+    config_prune_mib: u64,
+    config_rpc_user: String,
+    config_zmq: bool,
+    config_electrs_db: String,
+    /// Last linted bitcoin.conf and its findings (or why it could not be linted).
+    config_lint: Option<(PathBuf, Result<Vec<LintFinding>, String>)>,
+
+    // Option diff between two built versions
+    show_compare: bool,
+    compare_catalogs: Vec<OptionCatalog>,
+    /// Indices into `compare_catalogs`.
+    compare_from: usize,
+    compare_to: usize,
+
+    // Node process manager
+    show_nodes: bool,
+    nodes: HashMap<NodeKind, NodeConsole>,
+    /// Built versions per kind, newest first.  Refreshed when the window
+    /// opens, after installs and after builds — not every frame.
+    node_versions: HashMap<NodeKind, Vec<String>>,
+    /// Terminal tab on show: the build log (`None`) or a node's output.
+    terminal_tab: Option<NodeKind>,
+
+    // Install manager
+    show_install: bool,
+    /// Bin directory binaries are linked into.
+    install_bin_dir: String,
+    /// `binaries/<project>-<version>` folders available to install.
+    install_built: Vec<PathBuf>,
+    install_projects: Vec<InstalledProject>,
+    /// Also write a `.zip` when packaging a build.
+    package_zip: bool,
+    /// Account the generated units run as.
+    service_user: String,
+    /// Server-side paths the generated units use; the configs are optional.
+    unit_bin_dir: String,
+    unit_bitcoin_datadir: String,
+    unit_bitcoin_conf: String,
+    unit_electrs_db_dir: String,
+    unit_electrs_conf: String,
+
+    // Storage manager
+    show_storage: bool,
+    storage: Vec<StorageEntry>,
+    storage_scanning: bool,
+    /// Age threshold for "Delete older than".
+    storage_max_age_days: u64,
+    /// Cleanup awaiting confirmation, with its description.
+    storage_pending: Option<(String, CleanupPlan)>,
 
     // Modal
     modal: Option<Modal>,
 
     // Channels
     msg_rx: Receiver<AppMessage>,
-    msg_tx: Sender<AppMessage>,
+    msg_tx: UiSender<AppMessage>,
     confirm_rx: Receiver<ConfirmRequest>,
-    confirm_tx: Sender<ConfirmRequest>,
+    confirm_tx: UiSender<ConfirmRequest>,
 
     // Runtime
     runtime: Arc<Runtime>,
@@ -111,7 +271,7 @@ pub struct BitForgeApp {
 
 impl BitForgeApp {
     pub fn new(
-        _cc: &eframe::CreationContext<'_>,
+        cc: &eframe::CreationContext<'_>,
         runtime: Arc<Runtime>,
         msg_rx: Receiver<AppMessage>,
         msg_tx: Sender<AppMessage>,
@@ -121,7 +281,7 @@ impl BitForgeApp {
         let max_cores = std::thread::available_parallelism()
             .map(std::num::NonZero::get)
             .unwrap_or(1);
-        let default_cores = max_cores.saturating_sub(1).max(1);
+        let job_advice = recommend_jobs("Bitcoin", max_cores);
 
         let brew = find_brew();
         let brew_pfx = brew.as_deref().map(brew_prefix);
@@ -145,8 +305,11 @@ impl BitForgeApp {
 
         let mut app = Self {
             target: "Bitcoin".to_owned(),
-            cores: default_cores,
+            cores: job_advice.jobs,
             max_cores,
+            job_advice,
+            retry_oom: false,
+            signing_key: String::new(),
             build_dir: default_build_dir,
 
             bitcoin_versions: vec!["Loading...".to_owned()],
@@ -154,18 +317,90 @@ impl BitForgeApp {
             electrs_versions: vec!["Loading...".to_owned()],
             selected_electrs: "Loading...".to_owned(),
 
-            log_buffer: String::new(),
-            log_line_count: 0,
+            log_lines: VecDeque::new(),
+            log_index: LogIndex::default(),
+            log_index_dirty: false,
+            log_file: None,
+            last_log_file: None,
+            log_search: String::new(),
+            log_filter: LogFilter::All,
+            log_match_index: 0,
+            log_scroll_to_match: false,
+            log_show_timestamps: false,
+            collapsed_commands: HashSet::new(),
             progress: 0.0,
             is_busy: false,
             status_bar,
+            system_stats: None,
+
+            history: history::load(),
+            show_history: false,
+            history_target_filter: "All".to_owned(),
+            history_result_filter: None,
+
+            show_config: false,
+            config_caps: None,
+            config_network: Network::Mainnet,
+            config_datadir: default_bitcoin_datadir(),
+            config_prune: false,
+            config_prune_mib: 10_000,
+            config_rpc_user: "bitforge".to_owned(),
+            config_zmq: false,
+            config_electrs_db: home_dir()
+                .map(|h| h.join(".electrs").join("db").to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            config_lint: None,
+
+            show_compare: false,
+            compare_catalogs: Vec::new(),
+            compare_from: 0,
+            compare_to: 0,
+
+            show_nodes: false,
+            node_versions: HashMap::new(),
+            nodes: HashMap::from([
+                (
+                    NodeKind::Bitcoind,
+                    NodeConsole::new(default_bitcoin_datadir()),
+                ),
+                (
+                    NodeKind::Electrs,
+                    NodeConsole::new(
+                        home_dir()
+                            .map(|h| h.join(".electrs").join("db").to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                ),
+            ]),
+            terminal_tab: None,
+
+            show_install: false,
+            install_bin_dir: home_dir().map_or_else(
+                || "/usr/local/bin".to_owned(),
+                |h| h.join(".local/bin").to_string_lossy().into_owned(),
+            ),
+            install_built: Vec::new(),
+            install_projects: Vec::new(),
+            package_zip: false,
+            service_user: "bitcoin".to_owned(),
+            unit_bin_dir: "/usr/local/bin".to_owned(),
+            unit_bitcoin_datadir: "/var/lib/bitcoind".to_owned(),
+            unit_bitcoin_conf: String::new(),
+            unit_electrs_db_dir: "/var/lib/electrs".to_owned(),
+            unit_electrs_conf: String::new(),
+
+            show_storage: false,
+            storage: Vec::new(),
+            storage_scanning: false,
+            storage_max_age_days: 30,
+            storage_pending: None,
 
             modal: None,
 
             msg_rx,
-            msg_tx,
+            msg_tx: UiSender::new(msg_tx, cc.egui_ctx.clone()),
             confirm_rx,
-            confirm_tx,
+            confirm_tx: UiSender::new(confirm_tx, cc.egui_ctx.clone()),
 
             runtime,
 
@@ -189,52 +424,102 @@ impl BitForgeApp {
         app.append_log("📝 Bitcoin Core and Electrs are compiled from source via GitHub.\n\n");
 
         app.spawn_refresh_all_versions();
+        app.reattach_nodes();
         app
     }
 
     // ─── Log helpers ──────────────────────────────────────────────────────────
 
+    /// Append an app-level status message.
     fn append_log(&mut self, msg: &str) {
-        // Process character-by-character so that bare \r (carriage return)
-        // gets true terminal semantics: go back to the start of the current
-        // line and overwrite it.  This keeps cmake/make/git progress lines
-        // as a single updating line instead of hundreds of stacked copies.
-        for ch in msg.chars() {
-            match ch {
-                '\r' => {
-                    // Carriage return: truncate back to the last newline so
-                    // the next characters overwrite the current line.
-                    if let Some(pos) = self.log_buffer.rfind('\n') {
-                        self.log_buffer.truncate(pos + 1);
+        self.append_record(&LogRecord::app(msg.to_owned()));
+    }
+
+    fn append_record(&mut self, record: &LogRecord) {
+        // Split on \r and \n so that a bare \r (carriage return) gets true
+        // terminal semantics: go back to the start of the current line and
+        // overwrite it.  This keeps cmake/make/git progress lines as a
+        // single updating line instead of hundreds of stacked copies.
+        let mut first_changed = None;
+        let mut rest = record.text.as_str();
+        while !rest.is_empty() {
+            if !self.log_lines.back().is_some_and(|l| l.continues(record)) {
+                self.log_lines.push_back(LogLine::open(record));
+            }
+            first_changed.get_or_insert(self.log_lines.len() - 1);
+            let Some(line) = self.log_lines.back_mut() else {
+                break;
+            };
+            match rest.find(['\r', '\n']) {
+                Some(at) => {
+                    if rest.as_bytes()[at] == b'\r' {
+                        line.text.clear();
                     } else {
-                        self.log_buffer.clear();
+                        line.text.push_str(&rest[..at]);
+                        line.complete = true;
                     }
-                    // Don't change log_line_count — we're still on the same line.
-                }
-                '\n' => {
-                    self.log_buffer.push('\n');
-                    self.log_line_count += 1;
+                    rest = &rest[at + 1..];
                 }
-                c => {
-                    self.log_buffer.push(c);
+                None => {
+                    line.text.push_str(rest);
+                    rest = "";
                 }
             }
         }
 
-        if self.log_line_count > MAX_LOG_LINES {
-            let drop_count = self.log_line_count.saturating_sub(TRIM_TO_LINES);
-            let mut remaining = drop_count;
-            if let Some(split_pos) = self.log_buffer.char_indices().find_map(|(i, c)| {
-                if c == '\n' {
-                    if remaining == 0 {
-                        return Some(i);
-                    }
-                    remaining -= 1;
-                }
-                None
-            }) {
-                self.log_buffer = self.log_buffer[split_pos + 1..].to_owned();
-                self.log_line_count = TRIM_TO_LINES;
+        let Some(first) = first_changed else {
+            return;
+        };
+        for i in first..self.log_lines.len() {
+            let style = i
+                .checked_sub(1)
+                .map_or_else(AnsiStyle::default, |prev| self.log_lines[prev].end_style);
+            self.log_lines[i].restyle(style);
+        }
+        // Re-index only the changed and new lines; a pending rebuild covers
+        // them anyway.
+        if !self.log_index_dirty {
+            self.log_index.truncate_lines(first);
+            self.log_index.push_lines(
+                &self.log_lines,
+                first,
+                self.log_filter,
+                &self.collapsed_commands,
+                &self.log_search,
+            );
+        }
+        let excess = self.log_lines.len().saturating_sub(MAX_LOG_LINES);
+        self.log_lines.drain(..excess);
+        // Keep the current search match selected as earlier ones drop off.
+        let matches_dropped = self.log_index.drop_front_lines(excess);
+        self.log_match_index = self.log_match_index.saturating_sub(matches_dropped);
+    }
+
+    /// Write log text, minus ANSI escapes, to the open build log file.
+    /// On a write error the file is closed so we don't retry every line.
+    fn write_log_file(&mut self, record: &LogRecord) {
+        let msg = &record.text;
+        let Some(file) = self.log_file.as_mut() else {
+            return;
+        };
+        if let Err(e) = file.write_all(ansi::strip(msg).as_bytes()) {
+            self.log_file = None;
+            self.append_log(&format!("\n⚠️  Stopped writing build log file: {e}\n"));
+        }
+    }
+
+    fn open_log_file(&mut self, path: PathBuf) {
+        match File::create(&path) {
+            Ok(file) => {
+                self.log_file = Some(BufWriter::new(file));
+                self.last_log_file = Some(path);
+            }
+            Err(e) => {
+                self.log_file = None;
+                self.append_log(&format!(
+                    "\n⚠️  Could not create build log file {}: {e}\n",
+                    path.display()
+                ));
             }
         }
     }
@@ -244,7 +529,10 @@ impl BitForgeApp {
     fn drain_messages(&mut self) {
         while let Ok(msg) = self.msg_rx.try_recv() {
             match msg {
-                AppMessage::Log(s) => self.append_log(&s),
+                AppMessage::Log(record) => {
+                    self.write_log_file(&record);
+                    self.append_record(&record);
+                }
                 AppMessage::Progress(v) => self.progress = v.clamp(0.0, 1.0),
                 AppMessage::BitcoinVersionsLoaded(versions) => {
                     if let Some(first) = versions.first() {
@@ -269,22 +557,97 @@ impl BitForgeApp {
                         is_error,
                     });
                 }
+                AppMessage::LogFileStarted(path) => self.open_log_file(path),
+                AppMessage::LogFileFinished => {
+                    if let Some(mut file) = self.log_file.take() {
+                        file.flush().ok();
+                    }
+                }
+                AppMessage::BuildRecorded(record) => self.history.push(record),
+                // A reading can still be queued after the monitor stopped.
+                AppMessage::SystemStats(stats) if self.is_busy => {
+                    self.system_stats = Some(stats);
+                }
+                AppMessage::SystemStats(_) => {}
+                AppMessage::ConfigCapabilities(caps) => {
+                    if !caps.networks.contains(&self.config_network) {
+                        self.config_network = Network::Mainnet;
+                    }
+                    self.config_caps = Some(caps);
+                }
+                AppMessage::NodeStarted(record) => {
+                    let kind = record.kind;
+                    if let Some(console) = self.nodes.get_mut(&kind) {
+                        console.starting = false;
+                    }
+                    self.append_log(&format!(
+                        "▶ Started {} {} (pid {})\n",
+                        kind.binary(),
+                        record.version,
+                        record.pid
+                    ));
+                    self.watch_node(record, false);
+                    self.terminal_tab = Some(kind);
+                }
+                AppMessage::NodeStartFailed(kind) => {
+                    if let Some(console) = self.nodes.get_mut(&kind) {
+                        console.starting = false;
+                    }
+                }
+                AppMessage::NodeOutput { kind, text } => {
+                    if let Some(console) = self.nodes.get_mut(&kind) {
+                        console.push_output(&text);
+                    }
+                }
+                AppMessage::NodeExited(kind) => {
+                    if let Some(console) = self.nodes.get_mut(&kind) {
+                        console.running = None;
+                        console.watcher = None;
+                        console.push_output(&format!("\n[{} exited]\n", kind.binary()));
+                    }
+                    self.append_log(&format!("⏹ {} has exited\n", kind.binary()));
+                }
+                AppMessage::InstallChanged => {
+                    self.refresh_install();
+                    self.refresh_node_versions();
+                }
+                AppMessage::StorageScanned(entries) => {
+                    self.storage = entries;
+                    self.storage_scanning = false;
+                }
                 AppMessage::TaskDone => {
+                    self.refresh_node_versions();
                     self.is_busy = false;
                     self.progress = 0.0;
+                    self.system_stats = None;
+                    self.job_advice = recommend_jobs(&self.target, self.max_cores);
                 }
             }
         }
 
-        if self.modal.is_none() {
-            if let Ok(req) = self.confirm_rx.try_recv() {
-                self.modal = Some(Modal::Confirm {
-                    title: req.title,
-                    message: req.message,
-                    response_tx: req.response_tx,
-                });
-            }
+        // Keep the on-disk log current so it can be opened mid-build.
+        if let Some(file) = self.log_file.as_mut() {
+            file.flush().ok();
+        }
+
+        self.show_pending_confirm();
+    }
+
+    /// Show the next queued `ConfirmRequest` if no modal is open.  Returns
+    /// whether one was shown.
+    fn show_pending_confirm(&mut self) -> bool {
+        if self.modal.is_some() {
+            return false;
         }
+        let Ok(req) = self.confirm_rx.try_recv() else {
+            return false;
+        };
+        self.modal = Some(Modal::Confirm {
+            title: req.title,
+            message: req.message,
+            response_tx: req.response_tx,
+        });
+        true
     }
 
     // ─── Background task spawners ─────────────────────────────────────────────
@@ -383,9 +746,50 @@ impl BitForgeApp {
         self.spawn_refresh_electrs_versions();
     }
 
+    fn spawn_export_diagnostics(&self) {
+        let settings = BundleSettings {
+            target: self.target.clone(),
+            bitcoin_version: self.selected_bitcoin.clone(),
+            electrs_version: self.selected_electrs.clone(),
+            cores: self.cores,
+            build_dir: PathBuf::from(&self.build_dir),
+            brew: self.brew.clone(),
+            brew_pfx: self.brew_pfx.clone(),
+            log_file: self.last_log_file.clone(),
+            terminal_log: self
+                .log_lines
+                .iter()
+                .map(|l| format!("{}\n", l.plain))
+                .collect(),
+        };
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+
+        self.runtime.spawn(async move {
+            let dialog = match export_bundle(settings, env, tx.clone()).await {
+                Ok(archive) => AppMessage::ShowDialog {
+                    title: "Diagnostics Exported".into(),
+                    message: format!(
+                        "Diagnostics bundle saved to:\n{}\n\nAttach it to your bug report.",
+                        archive.display()
+                    ),
+                    is_error: false,
+                },
+                Err(e) => AppMessage::ShowDialog {
+                    title: "Export Failed".into(),
+                    message: format!("Could not export diagnostics:\n{e:#}"),
+                    is_error: true,
+                },
+            };
+            tx.send(dialog).ok();
+        });
+    }
+
     fn spawn_compile(&mut self) {
         let target = self.target.clone();
         let cores = self.cores;
+        let retry_oom = self.retry_oom;
+        let signing_key = self.configured_signing_key();
         let build_dir = PathBuf::from(&self.build_dir);
         let bitcoin_ver = self.selected_bitcoin.clone();
         let electrs_ver = self.selected_electrs.clone();
@@ -415,14 +819,42 @@ impl BitForgeApp {
         self.progress = 0.0;
 
         self.runtime.spawn(async move {
+            let plan = BuildPlan {
+                build_bitcoin: target == "Bitcoin" || target == "Both",
+                build_electrs: target == "Electrs" || target == "Both",
+                bitcoin_version: &bitcoin_ver,
+                electrs_version: &electrs_ver,
+                build_dir: &build_dir,
+            };
+            log_msg(&tx, "\n🧪 Pre-flight checks...\n");
+            let checks = run_checks(&plan, &env).await;
+            let checklist = format_checklist(&checks);
+            log_msg(&tx, &checklist);
+            if has_failures(&checks) {
+                tx.send(AppMessage::ShowDialog {
+                    title: "Pre-flight Checks Failed".into(),
+                    message: format!("The build was not started:\n\n{checklist}"),
+                    is_error: true,
+                })
+                .ok();
+                tx.send(AppMessage::TaskDone).ok();
+                return;
+            }
+
             tx.send(AppMessage::Progress(0.05)).ok();
+            let opts = BuildOptions {
+                cores,
+                retry_oom,
+                signing_key: signing_key.as_deref(),
+            };
+            let monitor = tokio::spawn(monitor::run(build_dir.clone(), tx.clone()));
             let mut output_dirs: Vec<String> = Vec::new();
             let mut error_occurred = false;
 
             if target == "Bitcoin" || target == "Both" {
                 tx.send(AppMessage::Progress(0.1)).ok();
-                match compile_bitcoin(&bitcoin_ver, &build_dir, cores, &env, &tx).await {
-                    Ok(dir) => {
+                match build_project("Bitcoin", &bitcoin_ver, &build_dir, &opts, &env, &tx).await {
+                    Some(dir) => {
                         output_dirs.push(dir.to_string_lossy().into_owned());
                         tx.send(AppMessage::Progress(if target == "Both" {
                             0.5
@@ -431,16 +863,7 @@ impl BitForgeApp {
                         }))
                         .ok();
                     }
-                    Err(e) => {
-                        log_msg(&tx, &format!("\n❌ Compilation failed: {e}\n"));
-                        tx.send(AppMessage::ShowDialog {
-                            title: "Compilation Failed".into(),
-                            message: e.to_string(),
-                            is_error: true,
-                        })
-                        .ok();
-                        error_occurred = true;
-                    }
+                    None => error_occurred = true,
                 }
             }
 
@@ -451,21 +874,12 @@ impl BitForgeApp {
                     0.1
                 }))
                 .ok();
-                match compile_electrs(&electrs_ver, &build_dir, cores, &env, &tx).await {
-                    Ok(dir) => {
+                match build_project("Electrs", &electrs_ver, &build_dir, &opts, &env, &tx).await {
+                    Some(dir) => {
                         output_dirs.push(dir.to_string_lossy().into_owned());
                         tx.send(AppMessage::Progress(1.0)).ok();
                     }
-                    Err(e) => {
-                        log_msg(&tx, &format!("\n❌ Compilation failed: {e}\n"));
-                        tx.send(AppMessage::ShowDialog {
-                            title: "Compilation Failed".into(),
-                            message: e.to_string(),
-                            is_error: true,
-                        })
-                        .ok();
-                        error_occurred = true;
-                    }
+                    None => error_occurred = true,
                 }
             }
 
@@ -486,6 +900,53 @@ impl BitForgeApp {
                 .ok();
             }
 
+            monitor.abort();
+            tx.send(AppMessage::TaskDone).ok();
+        });
+    }
+
+    /// Run the selected Bitcoin Core and Electrs builds together on regtest.
+    fn spawn_validate(&mut self) {
+        let (bitcoin_dir, electrs_dir) = self.selected_binaries_dirs();
+        let plan = ValidationPlan {
+            bitcoin_dir,
+            electrs_dir,
+        };
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+
+        self.is_busy = true;
+        self.runtime.spawn(async move {
+            let dialog = match regtest::validate(&plan, &env, &tx).await {
+                Ok(report) => {
+                    let passed = regtest::passed(&report);
+                    let checklist = format_checklist(&report);
+                    log_msg(
+                        &tx,
+                        if passed {
+                            "✅ Regtest validation passed\n"
+                        } else {
+                            "❌ Regtest validation failed\n"
+                        },
+                    );
+                    AppMessage::ShowDialog {
+                        title: if passed {
+                            "Validation Passed"
+                        } else {
+                            "Validation Failed"
+                        }
+                        .into(),
+                        message: checklist,
+                        is_error: !passed,
+                    }
+                }
+                Err(e) => AppMessage::ShowDialog {
+                    title: "Validation Failed".into(),
+                    message: format!("{e:#}"),
+                    is_error: true,
+                },
+            };
+            tx.send(dialog).ok();
             tx.send(AppMessage::TaskDone).ok();
         });
     }
@@ -587,214 +1048,2104 @@ impl BitForgeApp {
                 }
             }
         }
+        // A confirmation queued behind the modal that just closed would
+        // otherwise wait for some unrelated repaint.
+        if action.is_some() && self.show_pending_confirm() {
+            ctx.request_repaint();
+        }
     }
 
-    // ─── Content renderer (called inside centred column) ──────────────────────
-
-    #[allow(clippy::too_many_lines)]
-    fn render_content(&mut self, ui: &mut egui::Ui) {
-        // Header
-        ui.vertical_centered(|ui| {
-            ui.label(
-                egui::RichText::new("⚙  BitForge")
-                    .size(26.0)
-                    .strong()
-                    .color(pal::TEXT_PRIMARY),
-            );
-            ui.add_space(2.0);
-            ui.label(
-                egui::RichText::new("Bitcoin Core & Electrs Compiler for macOS")
-                    .size(13.0)
-                    .color(pal::LABEL_MUTED),
-            );
-        });
-
-        ui.add_space(20.0);
-
-        // ── Step 1 ────────────────────────────────────────────────────────────
-        section_card(ui, "Step 1 — Check & Install Dependencies", |ui| {
-            ui.horizontal(|ui| {
-                ui.label(
-                    egui::RichText::new(
-                        "Scans for required Homebrew packages and the Rust toolchain.",
-                    )
-                    .size(12.5)
-                    .color(pal::LABEL_MUTED),
-                );
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui
-                        .add_enabled(!self.is_busy, accent_button("Check & Install"))
-                        .clicked()
-                    {
-                        self.spawn_check_deps();
-                    }
-                });
-            });
-        });
+    // ─── Build history window ─────────────────────────────────────────────────
 
-        ui.add_space(10.0);
+    fn render_history_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_history;
+        let mut to_open: Option<PathBuf> = None;
 
-        // ── Step 2 ────────────────────────────────────────────────────────────
-        section_card(ui, "Step 2 — Configure Build", |ui| {
-            egui::Grid::new("settings_grid")
-                .num_columns(4)
-                .spacing([14.0, 10.0])
-                .show(ui, |ui| {
-                    // Row 1: Target + Cores
+        egui::Window::new("Build History")
+            .open(&mut open)
+            .default_size([760.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
                     ui.label(egui::RichText::new("Target").color(pal::LABEL_MUTED));
-                    egui::ComboBox::from_id_source("target_combo")
-                        .selected_text(&self.target)
-                        .width(140.0)
-                        .show_ui(ui, |ui: &mut egui::Ui| {
-                            for opt in &["Bitcoin", "Electrs", "Both"] {
-                                ui.selectable_value(&mut self.target, opt.to_string(), *opt);
-                            }
-                        });
-
-                    ui.label(egui::RichText::new("CPU Cores").color(pal::LABEL_MUTED));
-                    ui.horizontal(|ui| {
-                        ui.add(
-                            egui::DragValue::new(&mut self.cores)
-                                .range(1..=self.max_cores)
-                                .speed(1.0),
-                        );
-                        ui.label(
-                            egui::RichText::new(format!("of {}", self.max_cores))
-                                .small()
-                                .color(pal::LABEL_MUTED),
-                        );
-                    });
-                    ui.end_row();
-
-                    // Row 2: Build directory
-                    ui.label(egui::RichText::new("Output Dir").color(pal::LABEL_MUTED));
-                    ui.add(
-                        egui::TextEdit::singleline(&mut self.build_dir)
-                            .desired_width(440.0)
-                            .font(egui::TextStyle::Monospace),
-                    );
-                    ui.label(""); // spacer
-                    if ui.button("Browse…").clicked() {
-                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
-                            self.build_dir = folder.to_string_lossy().into_owned();
-                        }
-                    }
-                    ui.end_row();
-                });
-        });
-
-        ui.add_space(10.0);
-
-        // ── Step 3 ────────────────────────────────────────────────────────────
-        section_card(ui, "Step 3 — Select Versions", |ui| {
-            egui::Grid::new("versions_grid")
-                .num_columns(4)
-                .spacing([14.0, 10.0])
-                .show(ui, |ui| {
-                    ui.label(egui::RichText::new("Bitcoin Core").color(pal::LABEL_MUTED));
-                    egui::ComboBox::from_id_source("bitcoin_combo")
-                        .selected_text(&self.selected_bitcoin)
-                        .width(200.0)
+                    egui::ComboBox::from_id_source("history_target_filter")
+                        .selected_text(&self.history_target_filter)
+                        .width(100.0)
                         .show_ui(ui, |ui: &mut egui::Ui| {
-                            for v in &self.bitcoin_versions {
+                            for opt in &["All", "Bitcoin", "Electrs"] {
                                 ui.selectable_value(
-                                    &mut self.selected_bitcoin,
-                                    v.clone(),
-                                    v.as_str(),
+                                    &mut self.history_target_filter,
+                                    opt.to_string(),
+                                    *opt,
                                 );
                             }
                         });
-                    if ui.button("↻  Refresh").clicked() {
-                        self.spawn_refresh_bitcoin_versions();
-                    }
-                    ui.label("");
-                    ui.end_row();
 
-                    ui.label(egui::RichText::new("Electrs").color(pal::LABEL_MUTED));
-                    egui::ComboBox::from_id_source("electrs_combo")
-                        .selected_text(&self.selected_electrs)
-                        .width(200.0)
+                    ui.add_space(10.0);
+                    ui.label(egui::RichText::new("Result").color(pal::LABEL_MUTED));
+                    egui::ComboBox::from_id_source("history_result_filter")
+                        .selected_text(self.history_result_filter.map_or("All", BuildResult::label))
+                        .width(100.0)
                         .show_ui(ui, |ui: &mut egui::Ui| {
-                            for v in &self.electrs_versions {
+                            ui.selectable_value(&mut self.history_result_filter, None, "All");
+                            for r in [BuildResult::Succeeded, BuildResult::Failed] {
                                 ui.selectable_value(
-                                    &mut self.selected_electrs,
-                                    v.clone(),
-                                    v.as_str(),
+                                    &mut self.history_result_filter,
+                                    Some(r),
+                                    r.label(),
                                 );
                             }
                         });
-                    if ui.button("↻  Refresh").clicked() {
-                        self.spawn_refresh_electrs_versions();
-                    }
-                    ui.label("");
-                    ui.end_row();
                 });
-        });
-
-        ui.add_space(10.0);
-
-        // ── Progress ──────────────────────────────────────────────────────────
-        section_card(ui, "Build Progress", |ui| {
-            let label = if self.is_busy {
-                format!("{:.0}%", self.progress * 100.0)
-            } else if self.progress >= 1.0 {
-                "Complete".to_owned()
-            } else {
-                "Idle".to_owned()
-            };
-
-            ui.horizontal(|ui| {
-                ui.add(
-                    egui::ProgressBar::new(self.progress)
-                        .desired_width(ui.available_width() - 56.0)
-                        .animate(self.is_busy)
-                        .text(""),
-                );
                 ui.add_space(6.0);
-                ui.label(egui::RichText::new(label).small().color(pal::LABEL_MUTED));
-            });
-        });
+                ui.separator();
 
-        ui.add_space(10.0);
+                let target_filter = self.history_target_filter.as_str();
+                let result_filter = self.history_result_filter;
+                let rows: Vec<&BuildRecord> = self
+                    .history
+                    .iter()
+                    .rev()
+                    .filter(|r| target_filter == "All" || r.target == target_filter)
+                    .filter(|r| result_filter.is_none_or(|f| r.result == f))
+                    .collect();
 
-        // ── Build log terminal — FIXED HEIGHT, never resizes ──────────────────
+                if rows.is_empty() {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("No matching builds yet.").color(pal::LABEL_MUTED),
+                    );
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::Grid::new("history_grid")
+                            .num_columns(7)
+                            .striped(true)
+                            .spacing([14.0, 6.0])
+                            .show(ui, |ui| {
+                                for heading in [
+                                    "Started (UTC)",
+                                    "Target",
+                                    "Version",
+                                    "Duration",
+                                    "Result",
+                                    "",
+                                    "",
+                                ] {
+                                    ui.label(egui::RichText::new(heading).strong());
+                                }
+                                ui.end_row();
+
+                                for record in rows {
+                                    ui.label(format_utc(record.started_at));
+                                    ui.label(&record.target);
+                                    ui.label(&record.version).on_hover_text(format!(
+                                        "{} cores\n{}",
+                                        record.cores,
+                                        record.flags.join(" ")
+                                    ));
+                                    ui.label(format_duration(record.duration_secs()));
+
+                                    let color = match record.result {
+                                        BuildResult::Succeeded => pal::SUCCESS,
+                                        BuildResult::Failed => pal::DANGER,
+                                    };
+                                    let result = ui.colored_label(color, record.result.label());
+                                    if let Some(err) = &record.error {
+                                        let hover = if record.diagnoses.is_empty() {
+                                            err.clone()
+                                        } else {
+                                            format!(
+                                                "{err}\n\nDiagnosis: {}",
+                                                record.diagnoses.join(", ")
+                                            )
+                                        };
+                                        result.on_hover_text(hover);
+                                    }
+
+                                    let log = record.log_file.as_ref().filter(|p| p.exists());
+                                    if ui
+                                        .add_enabled(log.is_some(), egui::Button::new("Log"))
+                                        .clicked()
+                                    {
+                                        to_open = log.cloned();
+                                    }
+                                    let out = record.output_dir.as_ref().filter(|p| p.exists());
+                                    if ui
+                                        .add_enabled(out.is_some(), egui::Button::new("Folder"))
+                                        .clicked()
+                                    {
+                                        to_open = out.cloned();
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        self.show_history = open;
+
+        if let Some(path) = to_open {
+            if let Err(e) = open_path(&path) {
+                self.modal = Some(Modal::Alert {
+                    title: "Could Not Open".into(),
+                    message: format!("Failed to open {}:\n{e}", path.display()),
+                    is_error: true,
+                });
+            }
+        }
+    }
+
+    // ─── Config window ────────────────────────────────────────────────────────
+
+    fn selected_binaries_dirs(&self) -> (PathBuf, PathBuf) {
+        let binaries = Path::new(&self.build_dir).join("binaries");
+        (
+            binaries.join(format!(
+                "bitcoin-{}",
+                self.selected_bitcoin.trim_start_matches('v')
+            )),
+            binaries.join(format!(
+                "electrs-{}",
+                self.selected_electrs.trim_start_matches('v')
+            )),
+        )
+    }
+
+    fn spawn_config_detect(&mut self) {
+        let (bitcoin_dir, electrs_dir) = self.selected_binaries_dirs();
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+        self.config_caps = None;
+        self.runtime.spawn(async move {
+            let caps = config_gen::detect(&bitcoin_dir, &electrs_dir, &env).await;
+            tx.send(AppMessage::ConfigCapabilities(caps)).ok();
+        });
+    }
+
+    fn spawn_config_write(&mut self, caps: &Capabilities) {
+        let opts = ConfigOptions {
+            network: self.config_network,
+            datadir: self.config_datadir.trim().to_owned(),
+            prune_mib: self.config_prune.then_some(self.config_prune_mib),
+            rpc_user: self.config_rpc_user.clone(),
+            zmq: self.config_zmq,
+            electrs_db_dir: self.config_electrs_db.trim().to_owned(),
+        };
+        let config =
+            match config_gen::generate(&opts, caps, &self.selected_bitcoin, &self.selected_electrs)
+            {
+                Ok(config) => config,
+                Err(e) => {
+                    self.modal = Some(Modal::Alert {
+                        title: "Could Not Generate".into(),
+                        message: format!("{e:#}"),
+                        is_error: true,
+                    });
+                    return;
+                }
+            };
+        let out_dir = Path::new(&self.build_dir).join("configs").join(format!(
+            "bitcoin-{}",
+            self.selected_bitcoin.trim_start_matches('v')
+        ));
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            log_msg(&tx, "\n📝 Writing configuration files...\n");
+            let dialog = match config_gen::write(&config, &out_dir, &tx).await {
+                Ok(paths) => {
+                    let files = paths
+                        .iter()
+                        .map(|p| format!("• {}", p.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    let password = config.rpc_password.map_or_else(String::new, |p| {
+                        format!("\n\nRPC password (shown only once — store it now):\n{p}")
+                    });
+                    AppMessage::ShowDialog {
+                        title: "Configuration Written".into(),
+                        message: format!("{files}{password}"),
+                        is_error: false,
+                    }
+                }
+                Err(e) => AppMessage::ShowDialog {
+                    title: "Could Not Write Configuration".into(),
+                    message: format!("{e:#}"),
+                    is_error: true,
+                },
+            };
+            tx.send(dialog).ok();
+        });
+    }
+
+    fn render_config_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_config;
+        let mut generate: Option<Capabilities> = None;
+        let mut redetect = false;
+        let mut lint: Option<PathBuf> = None;
+
+        egui::Window::new("Configuration")
+            .open(&mut open)
+            .default_size([520.0, 360.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "For Bitcoin Core {} and Electrs {}",
+                        self.selected_bitcoin, self.selected_electrs
+                    ))
+                    .color(pal::LABEL_MUTED),
+                );
+                ui.add_space(6.0);
+                self.render_config_generator(ui, &mut generate, &mut redetect);
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(egui::RichText::new("Check an existing bitcoin.conf").strong());
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Reports options that Bitcoin Core {} does not accept, has deprecated \
+                         or only documents under -help-debug.",
+                        self.selected_bitcoin
+                    ))
+                    .small()
+                    .color(pal::LABEL_MUTED),
+                );
+                if ui.button("Choose bitcoin.conf…").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        lint = Some(path);
+                    }
+                }
+                if let Some((path, result)) = &self.config_lint {
+                    ui.add_space(4.0);
+                    ui.label(
+                        egui::RichText::new(path.to_string_lossy())
+                            .small()
+                            .monospace(),
+                    );
+                    match result {
+                        Err(e) => {
+                            ui.colored_label(pal::DANGER, e);
+                        }
+                        Ok(findings) if findings.is_empty() => {
+                            ui.colored_label(pal::SUCCESS, "✓ No problems found");
+                        }
+                        Ok(findings) => {
+                            egui::ScrollArea::vertical()
+                                .max_height(180.0)
+                                .show(ui, |ui| {
+                                    for f in findings {
+                                        let color = match f.level {
+                                            LintLevel::Error => pal::DANGER,
+                                            LintLevel::Warning | LintLevel::Note => {
+                                                pal::TEXT_PRIMARY
+                                            }
+                                        };
+                                        ui.colored_label(
+                                            color,
+                                            format!(
+                                                "{} line {}: {} — {}",
+                                                f.level.icon(),
+                                                f.line,
+                                                f.option,
+                                                f.message
+                                            ),
+                                        );
+                                    }
+                                });
+                        }
+                    }
+                }
+            });
+
+        self.show_config = open;
+        if redetect {
+            self.spawn_config_detect();
+        }
+        if let Some(path) = lint {
+            self.lint_bitcoin_conf(path);
+        }
+        if let Some(caps) = generate {
+            self.spawn_config_write(&caps);
+        }
+    }
+
+    /// Options offered by the selected builds, and the Generate button.
+    fn render_config_generator(
+        &mut self,
+        ui: &mut egui::Ui,
+        generate: &mut Option<Capabilities>,
+        redetect: &mut bool,
+    ) {
+        let Some(caps) = self.config_caps.clone() else {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Checking what the selected builds support…");
+            });
+            return;
+        };
+        if !caps.bitcoin_found {
+            ui.colored_label(
+                pal::DANGER,
+                "Bitcoin Core has not been built for the selected version.",
+            );
+            if ui.button("Check again").clicked() {
+                *redetect = true;
+            }
+            return;
+        }
+
+        egui::Grid::new("config_grid")
+            .num_columns(2)
+            .spacing([14.0, 8.0])
+            .show(ui, |ui| {
+                ui.label("Network");
+                egui::ComboBox::from_id_source("config_network")
+                    .selected_text(self.config_network.label())
+                    .show_ui(ui, |ui: &mut egui::Ui| {
+                        for n in &caps.networks {
+                            ui.selectable_value(&mut self.config_network, *n, n.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Data directory");
+                ui.vertical(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config_datadir)
+                            .desired_width(320.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    if let Some(error) = config_gen::datadir_error(self.config_datadir.trim()) {
+                        ui.colored_label(pal::DANGER, egui::RichText::new(error).small());
+                    }
+                });
+                ui.end_row();
+
+                if caps.prune {
+                    ui.label("Pruning");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.config_prune, "Prune to");
+                        ui.add_enabled(
+                            self.config_prune,
+                            egui::DragValue::new(&mut self.config_prune_mib)
+                                .range(550..=1_000_000)
+                                .suffix(" MiB"),
+                        );
+                    });
+                    ui.end_row();
+                }
+
+                if caps.rpcauth {
+                    ui.label("RPC user");
+                    ui.vertical(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config_rpc_user)
+                                .desired_width(160.0)
+                                .hint_text("none — cookie auth only"),
+                        );
+                        if let Some(error) = config_gen::rpc_user_error(self.config_rpc_user.trim())
+                        {
+                            ui.colored_label(pal::DANGER, egui::RichText::new(error).small());
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                if caps.zmq {
+                    ui.label("ZMQ");
+                    ui.checkbox(&mut self.config_zmq, "Publish raw blocks and transactions");
+                    ui.end_row();
+                }
+
+                if caps.electrs_found {
+                    ui.label("Electrs DB");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config_electrs_db)
+                            .desired_width(320.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    ui.end_row();
+                }
+            });
+
+        if !caps.zmq {
+            ui.label(
+                egui::RichText::new("ZMQ is not available: this build was configured without it.")
+                    .small()
+                    .color(pal::LABEL_MUTED),
+            );
+        }
+        if !caps.electrs_found {
+            ui.label(
+                egui::RichText::new("Electrs has not been built for the selected version; only bitcoin.conf is generated.")
+                    .small()
+                    .color(pal::LABEL_MUTED),
+            );
+        } else if self.config_prune && caps.prune {
+            ui.colored_label(
+                pal::DANGER,
+                egui::RichText::new("electrs needs an unpruned node.").small(),
+            );
+        }
+
+        ui.add_space(8.0);
+        if ui
+            .add_enabled(
+                !self.config_datadir.trim().is_empty()
+                    && config_gen::datadir_error(self.config_datadir.trim()).is_none()
+                    && (!caps.rpcauth
+                        || config_gen::rpc_user_error(self.config_rpc_user.trim()).is_none()),
+                egui::Button::new("Generate"),
+            )
+            .clicked()
+        {
+            *generate = Some(caps);
+        }
+    }
+
+    /// Lint the bitcoin.conf at `path` against the selected version's option
+    /// catalog.  Small files, read on the UI thread.
+    fn lint_bitcoin_conf(&mut self, path: PathBuf) {
+        let (bitcoin_dir, _) = self.selected_binaries_dirs();
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read the file: {e}"))
+            .and_then(|text| {
+                let target =
+                    options::load(&bitcoin_dir.join(options::CATALOG_FILE)).map_err(|_| {
+                        format!(
+                            "No option catalog for Bitcoin Core {} — rebuild it to capture one.",
+                            self.selected_bitcoin
+                        )
+                    })?;
+                let others: Vec<_> =
+                    options::load_all(&Path::new(&self.build_dir).join("binaries"))
+                        .into_iter()
+                        .map(|(_, c)| c)
+                        .filter(|c| c.binary == target.binary)
+                        .collect();
+                Ok(options::lint(&text, &target, &others))
+            });
+        self.config_lint = Some((path, result));
+    }
+
+    // ─── Compare window ───────────────────────────────────────────────────────
+
+    /// Load every captured catalog, oldest version first per binary, and
+    /// preselect the two newest versions of the first binary.
+    fn load_compare_catalogs(&mut self) {
+        let mut catalogs: Vec<OptionCatalog> =
+            options::load_all(&Path::new(&self.build_dir).join("binaries"))
+                .into_iter()
+                .map(|(_, c)| c)
+                .collect();
+        catalogs.sort_by(|a, b| {
+            a.binary.cmp(&b.binary).then_with(|| {
+                options::version_key(&a.version).cmp(&options::version_key(&b.version))
+            })
+        });
+        let first = catalogs.first().map(|c| c.binary.clone());
+        let same: Vec<usize> = (0..catalogs.len())
+            .filter(|&i| Some(&catalogs[i].binary) == first.as_ref())
+            .collect();
+        self.compare_to = same.last().copied().unwrap_or(0);
+        self.compare_from = same
+            .len()
+            .checked_sub(2)
+            .map_or(self.compare_to, |i| same[i]);
+        self.compare_catalogs = catalogs;
+    }
+
+    fn render_compare_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_compare;
+        let mut reload = false;
+
+        egui::Window::new("Compare Options")
+            .open(&mut open)
+            .default_size([640.0, 460.0])
+            .show(ctx, |ui| {
+                if self.compare_catalogs.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            "No option catalogs yet — they are captured when a build succeeds.",
+                        )
+                        .color(pal::LABEL_MUTED),
+                    );
+                    if ui.button("Refresh").clicked() {
+                        reload = true;
+                    }
+                    return;
+                }
+
+                let label = |c: &OptionCatalog| format!("{} {}", c.binary, c.version);
+                ui.horizontal(|ui| {
+                    for (id, caption, index) in [
+                        ("compare_from", "From", &mut self.compare_from),
+                        ("compare_to", "to", &mut self.compare_to),
+                    ] {
+                        ui.label(egui::RichText::new(caption).color(pal::LABEL_MUTED));
+                        egui::ComboBox::from_id_source(id)
+                            .selected_text(label(&self.compare_catalogs[*index]))
+                            .width(160.0)
+                            .show_ui(ui, |ui: &mut egui::Ui| {
+                                for (i, c) in self.compare_catalogs.iter().enumerate() {
+                                    ui.selectable_value(index, i, label(c));
+                                }
+                            });
+                    }
+                    if ui.button("Refresh").clicked() {
+                        reload = true;
+                    }
+                });
+
+                let from = &self.compare_catalogs[self.compare_from];
+                let to = &self.compare_catalogs[self.compare_to];
+                ui.add_space(6.0);
+                if from.binary != to.binary {
+                    ui.colored_label(pal::DANGER, "Pick two versions of the same binary.");
+                    return;
+                }
+                let diff = options::diff(from, to);
+                if diff.is_empty() {
+                    ui.colored_label(pal::SUCCESS, "✓ No option changes");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let entry_row = |ui: &mut egui::Ui, e: &OptionEntry, extra: String| {
+                        ui.label(egui::RichText::new(format!("-{}{extra}", e.name)).monospace());
+                        if !e.description.is_empty() {
+                            ui.label(
+                                egui::RichText::new(&e.description)
+                                    .small()
+                                    .color(pal::LABEL_MUTED),
+                            );
+                        }
+                    };
+                    egui::CollapsingHeader::new(format!("Added ({})", diff.added.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for e in &diff.added {
+                                entry_row(ui, e, String::new());
+                            }
+                        });
+                    egui::CollapsingHeader::new(format!("Removed ({})", diff.removed.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for e in &diff.removed {
+                                entry_row(ui, e, String::new());
+                            }
+                        });
+                    egui::CollapsingHeader::new(format!(
+                        "Changed default ({})",
+                        diff.changed.len()
+                    ))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for (old, new) in &diff.changed {
+                            let none = || "none".to_owned();
+                            entry_row(
+                                ui,
+                                new,
+                                format!(
+                                    ":  {}  →  {}",
+                                    old.default.clone().unwrap_or_else(none),
+                                    new.default.clone().unwrap_or_else(none)
+                                ),
+                            );
+                        }
+                    });
+                });
+            });
+
+        self.show_compare = open;
+        if reload {
+            self.load_compare_catalogs();
+        }
+    }
+
+    // ─── Nodes window ─────────────────────────────────────────────────────────
+
+    /// Pick up nodes left running by an earlier session.
+    fn reattach_nodes(&mut self) {
+        for kind in NodeKind::ALL {
+            if let Some(record) = nodes::load_running(kind) {
+                self.append_log(&format!(
+                    "🔗 {} {} is still running (pid {})\n",
+                    kind.binary(),
+                    record.version,
+                    record.pid
+                ));
+                self.watch_node(record, true);
+            }
+        }
+    }
+
+    fn watch_node(&mut self, record: NodeRecord, reattach: bool) {
+        let Some(console) = self.nodes.get_mut(&record.kind) else {
+            return;
+        };
+        if let Some(old) = console.watcher.take() {
+            old.abort();
+        }
+        if !reattach {
+            console.lines.clear();
+            console.partial.clear();
+        }
+        let task = self
+            .runtime
+            .spawn(nodes::watch(record.clone(), reattach, self.msg_tx.clone()));
+        console.watcher = Some(task.abort_handle());
+        console.running = Some(record);
+    }
+
+    /// Built versions of `kind`, newest first.
+    fn refresh_node_versions(&mut self) {
+        self.node_versions = NodeKind::ALL
+            .into_iter()
+            .map(|k| (k, self.built_versions(k)))
+            .collect();
+    }
+
+    fn built_versions(&self, kind: NodeKind) -> Vec<String> {
+        let mut versions: Vec<String> =
+            std::fs::read_dir(Path::new(&self.build_dir).join("binaries"))
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    install::parse_build_name(&name)
+                        .filter(|(project, _)| *project == kind.project())
+                        .map(|(_, version)| version.to_owned())
+                })
+                .filter(|v| {
+                    Path::new(&self.build_dir)
+                        .join("binaries")
+                        .join(format!("{}-{v}", kind.project()))
+                        .join(kind.binary())
+                        .is_file()
+                })
+                .collect();
+        versions.sort_by_key(|v| std::cmp::Reverse(options::version_key(v)));
+        versions
+    }
+
+    fn spawn_node_start(&mut self, kind: NodeKind) {
+        let Some(console) = self.nodes.get_mut(&kind) else {
+            return;
+        };
+        let config = console.config.trim();
+        let launch = NodeLaunch {
+            kind,
+            binaries_dir: Path::new(&self.build_dir).join("binaries").join(format!(
+                "{}-{}",
+                kind.project(),
+                console.version
+            )),
+            version: console.version.clone(),
+            config: (!config.is_empty()).then(|| PathBuf::from(config)),
+            datadir: PathBuf::from(console.datadir.trim()),
+        };
+        // Until the outcome arrives, so a double click can't start two.
+        console.starting = true;
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            match nodes::start(&launch, &env).await {
+                Ok(record) => {
+                    tx.send(AppMessage::NodeStarted(record)).ok();
+                }
+                Err(e) => {
+                    tx.send(AppMessage::NodeStartFailed(kind)).ok();
+                    tx.send(AppMessage::ShowDialog {
+                        title: format!("Could Not Start {}", kind.binary()),
+                        message: format!("{e:#}"),
+                        is_error: true,
+                    })
+                    .ok();
+                }
+            }
+        });
+    }
+
+    fn spawn_node_stop(&mut self, record: NodeRecord) {
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            nodes::stop(&record, &env, &tx).await;
+        });
+    }
+
+    fn render_nodes_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_nodes;
+        let mut start: Option<NodeKind> = None;
+        let mut stop: Option<NodeRecord> = None;
+        let versions = &self.node_versions;
+
+        egui::Window::new("Nodes")
+            .open(&mut open)
+            .default_size([560.0, 380.0])
+            .show(ctx, |ui| {
+                for kind in NodeKind::ALL {
+                    let Some(console) = self.nodes.get_mut(&kind) else {
+                        continue;
+                    };
+                    let built = versions.get(&kind).map_or(&[][..], Vec::as_slice);
+                    if console.version.is_empty() || !built.contains(&console.version) {
+                        console.version = built.first().cloned().unwrap_or_default();
+                    }
+                    let running = console.running.is_some();
+
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(kind.binary()).strong().monospace());
+                        match &console.running {
+                            Some(r) => ui.colored_label(
+                                pal::SUCCESS,
+                                format!("● running {} · pid {}", r.version, r.pid),
+                            ),
+                            None => ui.label(
+                                egui::RichText::new("○ stopped").color(pal::LABEL_MUTED),
+                            ),
+                        };
+                    });
+                    egui::Grid::new(("nodes_grid", kind))
+                        .num_columns(2)
+                        .spacing([14.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label("Version");
+                            ui.add_enabled_ui(!running, |ui| {
+                                egui::ComboBox::from_id_source(("node_version", kind))
+                                    .selected_text(if built.is_empty() {
+                                        "none built"
+                                    } else {
+                                        console.version.as_str()
+                                    })
+                                    .show_ui(ui, |ui: &mut egui::Ui| {
+                                        for v in built {
+                                            ui.selectable_value(&mut console.version, v.clone(), v);
+                                        }
+                                    });
+                            });
+                            ui.end_row();
+
+                            for (label, value, pick_folder) in [
+                                ("Config", &mut console.config, false),
+                                (
+                                    if kind == NodeKind::Bitcoind {
+                                        "Datadir"
+                                    } else {
+                                        "DB dir"
+                                    },
+                                    &mut console.datadir,
+                                    true,
+                                ),
+                            ] {
+                                ui.label(label);
+                                ui.add_enabled_ui(!running, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(value)
+                                                .desired_width(300.0)
+                                                .font(egui::TextStyle::Monospace)
+                                                .hint_text(if pick_folder {
+                                                    ""
+                                                } else {
+                                                    "optional"
+                                                }),
+                                        );
+                                        if ui.button("Browse…").clicked() {
+                                            let dialog = rfd::FileDialog::new();
+                                            let picked = if pick_folder {
+                                                dialog.pick_folder()
+                                            } else {
+                                                dialog.pick_file()
+                                            };
+                                            if let Some(path) = picked {
+                                                *value = path.to_string_lossy().into_owned();
+                                            }
+                                        }
+                                    });
+                                });
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.horizontal(|ui| {
+                        if let Some(record) = &console.running {
+                            if ui.button("⏹  Stop").clicked() {
+                                stop = Some(record.clone());
+                            }
+                        } else if ui
+                            .add_enabled(
+                                !console.starting
+                                    && !console.version.is_empty()
+                                    && !console.datadir.trim().is_empty(),
+                                egui::Button::new("▶  Start"),
+                            )
+                            .clicked()
+                        {
+                            start = Some(kind);
+                        }
+                        if ui.button("Show output").clicked() {
+                            self.terminal_tab = Some(kind);
+                        }
+                    });
+                    ui.add_space(8.0);
+                    ui.separator();
+                }
+                ui.label(
+                    egui::RichText::new(
+                        "Nodes keep running when BitForge quits and are picked up again on the next launch.",
+                    )
+                    .small()
+                    .color(pal::LABEL_MUTED),
+                );
+            });
+
+        self.show_nodes = open;
+        if let Some(kind) = start {
+            self.spawn_node_start(kind);
+        }
+        if let Some(record) = stop {
+            self.spawn_node_stop(record);
+        }
+    }
+
+    /// A node's output, in the same frame as the build log terminal.
+    fn render_node_terminal(&self, ui: &mut egui::Ui, kind: NodeKind) {
+        let Some(console) = self.nodes.get(&kind) else {
+            return;
+        };
+        let row_height = ui.fonts(|f| f.row_height(&egui::FontId::monospace(TERMINAL_FONT_SIZE)));
+        let partial = usize::from(!console.partial.is_empty());
+
+        egui::Frame {
+            fill: pal::TERM_BG,
+            stroke: egui::Stroke::new(1.0, pal::TERM_BORDER),
+            inner_margin: egui::Margin::same(10.0),
+            rounding: egui::Rounding::same(8.0),
+            outer_margin: egui::Margin::ZERO,
+            ..Default::default()
+        }
+        .show(ui, |ui| {
+            ui.set_min_height(TERMINAL_HEIGHT);
+            ui.set_max_height(TERMINAL_HEIGHT);
+            ui.spacing_mut().item_spacing.y = 0.0;
+
+            egui::ScrollArea::both()
+                .id_source(("node_log", kind))
+                .stick_to_bottom(true)
+                .max_height(TERMINAL_HEIGHT)
+                .min_scrolled_height(TERMINAL_HEIGHT)
+                .auto_shrink([false, false])
+                .show_rows(ui, row_height, console.lines.len() + partial, |ui, rows| {
+                    for row in rows {
+                        let text = console
+                            .lines
+                            .get(row)
+                            .map_or(console.partial.as_str(), String::as_str);
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(text)
+                                    .monospace()
+                                    .size(TERMINAL_FONT_SIZE)
+                                    .color(pal::TERM_TEXT),
+                            )
+                            .wrap_mode(egui::TextWrapMode::Extend),
+                        );
+                    }
+                });
+        });
+    }
+
+    // ─── Install window ───────────────────────────────────────────────────────
+
+    /// Re-read the built folders and what is installed.  Only directory
+    /// listings, so it runs on the UI thread.
+    fn refresh_install(&mut self) {
+        let binaries = Path::new(&self.build_dir).join("binaries");
+        let mut built: Vec<PathBuf> = std::fs::read_dir(binaries)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(install::parse_build_name)
+                    .is_some()
+            })
+            .collect();
+        built.sort();
+        self.install_built = built;
+        self.install_projects = install::installed(Path::new(&self.install_bin_dir));
+    }
+
+    fn spawn_install(&mut self, binaries_dir: PathBuf) {
+        let bin_dir = PathBuf::from(&self.install_bin_dir);
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = install::install(&binaries_dir, &bin_dir, &tx).await {
+                log_msg(&tx, &format!("❌ Install failed: {e:#}\n"));
+                tx.send(AppMessage::ShowDialog {
+                    title: "Install Failed".into(),
+                    message: format!("{e:#}"),
+                    is_error: true,
+                })
+                .ok();
+            }
+            tx.send(AppMessage::InstallChanged).ok();
+        });
+    }
+
+    /// The gpg key from Step 2, if one is set.
+    fn configured_signing_key(&self) -> Option<String> {
+        Some(self.signing_key.trim().to_owned()).filter(|k| !k.is_empty())
+    }
+
+    fn spawn_package(&mut self, binaries_dir: PathBuf) {
+        let with_zip = self.package_zip;
+        let signing_key = self.configured_signing_key();
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            let result = package::package(&binaries_dir, with_zip, signing_key, env, &tx).await;
+            let dialog = match result {
+                Ok(archives) => AppMessage::ShowDialog {
+                    title: "Package Created".into(),
+                    message: archives
+                        .iter()
+                        .map(|p| format!("• {}", p.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    is_error: false,
+                },
+                Err(e) => {
+                    log_msg(&tx, &format!("❌ Packaging failed: {e:#}\n"));
+                    AppMessage::ShowDialog {
+                        title: "Packaging Failed".into(),
+                        message: format!("{e:#}"),
+                        is_error: true,
+                    }
+                }
+            };
+            tx.send(dialog).ok();
+        });
+    }
+
+    fn spawn_verify(&mut self, binaries_dir: PathBuf) {
+        let signing_key = self.configured_signing_key();
+        let env = setup_build_environment(self.brew_pfx.as_deref());
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            let result = signing::verify(&binaries_dir, signing_key.as_deref(), &env, &tx).await;
+            let dialog = match result {
+                Ok(report) => {
+                    let checklist = format_checklist(&report);
+                    log_msg(&tx, &checklist);
+                    let failed = has_failures(&report);
+                    AppMessage::ShowDialog {
+                        title: if failed {
+                            "Verification Failed".into()
+                        } else {
+                            "Verified".into()
+                        },
+                        message: checklist,
+                        is_error: failed,
+                    }
+                }
+                Err(e) => AppMessage::ShowDialog {
+                    title: "Verification Failed".into(),
+                    message: format!("{e:#}"),
+                    is_error: true,
+                },
+            };
+            tx.send(dialog).ok();
+        });
+    }
+
+    fn spawn_activate(&mut self, project: String, version: String) {
+        let bin_dir = PathBuf::from(&self.install_bin_dir);
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = install::activate(&bin_dir, &project, &version, &tx).await {
+                log_msg(&tx, &format!("❌ Could not switch version: {e:#}\n"));
+            }
+            tx.send(AppMessage::InstallChanged).ok();
+        });
+    }
+
+    fn unit_settings(&self) -> UnitSettings {
+        let path = |s: &str| (!s.trim().is_empty()).then(|| PathBuf::from(s.trim()));
+        UnitSettings {
+            bin_dir: PathBuf::from(self.unit_bin_dir.trim()),
+            user: self.service_user.trim().to_owned(),
+            bitcoin_version: self.selected_bitcoin.clone(),
+            bitcoin_datadir: PathBuf::from(self.unit_bitcoin_datadir.trim()),
+            bitcoin_conf: path(&self.unit_bitcoin_conf),
+            electrs_version: self.selected_electrs.clone(),
+            electrs_db_dir: PathBuf::from(self.unit_electrs_db_dir.trim()),
+            electrs_conf: path(&self.unit_electrs_conf),
+        }
+    }
+
+    fn spawn_write_units(&mut self) {
+        let settings = self.unit_settings();
+        let (bitcoin_dir, electrs_dir) = self.selected_binaries_dirs();
+        let tx = self.msg_tx.clone();
+        self.runtime.spawn(async move {
+            log_msg(&tx, "\n🧩 Writing systemd units...\n");
+            let result = systemd::write_units(&settings, &bitcoin_dir, &electrs_dir, &tx).await;
+            let dialog = match result {
+                Ok(paths) => AppMessage::ShowDialog {
+                    title: "systemd Units Written".into(),
+                    message: paths
+                        .iter()
+                        .map(|p| format!("• {}", p.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    is_error: false,
+                },
+                Err(e) => AppMessage::ShowDialog {
+                    title: "Could Not Write Units".into(),
+                    message: format!("{e:#}"),
+                    is_error: true,
+                },
+            };
+            tx.send(dialog).ok();
+        });
+    }
+
+    fn render_install_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_install;
+        let mut refresh = false;
+        let mut to_install: Option<PathBuf> = None;
+        let mut to_activate: Option<(String, String)> = None;
+        let mut write_units = false;
+        let mut to_package: Option<PathBuf> = None;
+        let mut to_verify: Option<PathBuf> = None;
+
+        egui::Window::new("Install")
+            .open(&mut open)
+            .default_size([600.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("Bin directory").color(pal::LABEL_MUTED));
+                    let field = ui.add(
+                        egui::TextEdit::singleline(&mut self.install_bin_dir)
+                            .desired_width(300.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    if field.lost_focus() {
+                        refresh = true;
+                    }
+                    if ui.button("Browse…").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            self.install_bin_dir = folder.to_string_lossy().into_owned();
+                            refresh = true;
+                        }
+                    }
+                    if ui.button("Refresh").clicked() {
+                        refresh = true;
+                    }
+                });
+                let bin_dir = Path::new(&self.install_bin_dir);
+                if !install::on_path(bin_dir) {
+                    ui.colored_label(
+                        pal::DANGER,
+                        egui::RichText::new("This directory is not on your PATH.").small(),
+                    );
+                }
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Versions are kept in {}",
+                        install::install_root(bin_dir).display()
+                    ))
+                    .small()
+                    .color(pal::LABEL_MUTED),
+                );
+
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new("Built").strong());
+                if self.install_built.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No builds in the output directory yet.")
+                            .color(pal::LABEL_MUTED),
+                    );
+                }
+                egui::Grid::new("install_built_grid")
+                    .num_columns(4)
+                    .spacing([14.0, 6.0])
+                    .show(ui, |ui| {
+                        for dir in &self.install_built {
+                            let name = dir
+                                .file_name()
+                                .map(|n| n.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+                            ui.label(egui::RichText::new(name).monospace());
+                            if ui
+                                .add_enabled(!self.is_busy, egui::Button::new("Install"))
+                                .clicked()
+                            {
+                                to_install = Some(dir.clone());
+                            }
+                            if ui
+                                .add_enabled(!self.is_busy, egui::Button::new("Package"))
+                                .on_hover_text("tar.gz with a manifest, licenses and SHA256SUMS")
+                                .clicked()
+                            {
+                                to_package = Some(dir.clone());
+                            }
+                            if ui
+                                .add_enabled(
+                                    !self.is_busy && dir.join(package::SUMS_FILE).is_file(),
+                                    egui::Button::new("Verify"),
+                                )
+                                .on_hover_text("Check the files against the signed SHA256SUMS")
+                                .on_disabled_hover_text(
+                                    "Not signed — set a signing key and rebuild",
+                                )
+                                .clicked()
+                            {
+                                to_verify = Some(dir.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.checkbox(&mut self.package_zip, "Also package as .zip");
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(egui::RichText::new("Installed").strong());
+                if self.install_projects.is_empty() {
+                    ui.label(egui::RichText::new("Nothing installed yet.").color(pal::LABEL_MUTED));
+                }
+                for project in &self.install_projects {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(egui::RichText::new(&project.project).monospace());
+                        for version in &project.versions {
+                            let active = project.current.as_deref() == Some(version.as_str());
+                            if ui
+                                .selectable_label(active, version)
+                                .on_hover_text("Make this version current")
+                                .clicked()
+                                && !active
+                            {
+                                to_activate = Some((project.project.clone(), version.clone()));
+                            }
+                        }
+                    });
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(egui::RichText::new("systemd services").strong());
+                ui.label(
+                    egui::RichText::new(
+                        "Hardened units for a Linux server. Paths are on the server, \
+                         where the binaries must be installed separately.",
+                    )
+                    .small()
+                    .color(pal::LABEL_MUTED),
+                );
+                egui::Grid::new("unit_settings_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 6.0])
+                    .show(ui, |ui| {
+                        for (label, value, hint) in [
+                            ("Run as user", &mut self.service_user, ""),
+                            ("Bin directory", &mut self.unit_bin_dir, ""),
+                            ("bitcoind datadir", &mut self.unit_bitcoin_datadir, ""),
+                            ("bitcoind config", &mut self.unit_bitcoin_conf, "optional"),
+                            ("electrs DB dir", &mut self.unit_electrs_db_dir, ""),
+                            ("electrs config", &mut self.unit_electrs_conf, "optional"),
+                        ] {
+                            ui.label(label);
+                            ui.add(
+                                egui::TextEdit::singleline(value)
+                                    .desired_width(260.0)
+                                    .font(egui::TextStyle::Monospace)
+                                    .hint_text(hint),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                let problem = systemd::settings_error(&self.unit_settings());
+                if let Some(problem) = &problem {
+                    ui.colored_label(pal::DANGER, egui::RichText::new(problem).small());
+                }
+                if ui
+                    .add_enabled(
+                        !self.is_busy && problem.is_none(),
+                        egui::Button::new("Write units next to binaries"),
+                    )
+                    .on_hover_text("For copying to /etc/systemd/system on the server")
+                    .on_disabled_hover_text("Needs valid settings and no task running")
+                    .clicked()
+                {
+                    write_units = true;
+                }
+            });
+
+        self.show_install = open;
+        if let Some(dir) = to_package {
+            self.spawn_package(dir);
+        }
+        if let Some(dir) = to_verify {
+            self.spawn_verify(dir);
+        }
+        if write_units {
+            self.spawn_write_units();
+        }
+        if let Some(dir) = to_install {
+            self.spawn_install(dir);
+        }
+        if let Some((project, version)) = to_activate {
+            self.spawn_activate(project, version);
+        }
+        if refresh {
+            self.refresh_install();
+        }
+    }
+
+    // ─── Storage window ───────────────────────────────────────────────────────
+
+    fn spawn_storage_scan(&mut self) {
+        let build_dir = PathBuf::from(&self.build_dir);
+        let tx = self.msg_tx.clone();
+        self.storage_scanning = true;
+        self.runtime.spawn(async move {
+            let entries = tokio::task::spawn_blocking(move || storage::scan(&build_dir))
+                .await
+                .unwrap_or_default();
+            tx.send(AppMessage::StorageScanned(entries)).ok();
+        });
+    }
+
+    fn spawn_storage_cleanup(&mut self, plan: CleanupPlan) {
+        let entries = self.storage.clone();
+        let build_dir = PathBuf::from(&self.build_dir);
+        let tx = self.msg_tx.clone();
+        self.storage_scanning = true;
+        self.runtime.spawn(async move {
+            storage::delete(plan.paths, &entries, &tx).await;
+            let entries = tokio::task::spawn_blocking(move || storage::scan(&build_dir))
+                .await
+                .unwrap_or_default();
+            tx.send(AppMessage::StorageScanned(entries)).ok();
+        });
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn render_storage_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_storage;
+        let mut rescan = false;
+        let mut request: Option<(String, CleanupPlan)> = None;
+        let mut confirmed: Option<CleanupPlan> = None;
+        let mut cancel = false;
+        // Deleting while a build runs could pull files out from under it.
+        let can_delete = !self.is_busy && !self.storage_scanning;
+        let now = unix_now();
+        let in_use: Vec<PathBuf> = self
+            .nodes
+            .values()
+            .filter_map(|c| c.running.as_ref())
+            .map(|r| r.binary.clone())
+            .collect();
+
+        egui::Window::new("Storage")
+            .open(&mut open)
+            .default_size([720.0, 440.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(&self.build_dir)
+                            .monospace()
+                            .color(pal::LABEL_MUTED),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(!self.storage_scanning, egui::Button::new("Refresh"))
+                            .clicked()
+                        {
+                            rescan = true;
+                        }
+                        if self.storage_scanning {
+                            ui.spinner();
+                        }
+                    });
+                });
+                ui.add_space(4.0);
+
+                // ── Cleanup actions ───────────────────────────────────────────
+                let artifacts = plan_cleanup(&self.storage, Cleanup::Artifacts, now, &in_use);
+                let sources = plan_cleanup(&self.storage, Cleanup::Sources, now, &in_use);
+                let old = plan_cleanup(
+                    &self.storage,
+                    Cleanup::OlderThan(self.storage_max_age_days),
+                    now,
+                    &in_use,
+                );
+                let total: u64 = self
+                    .storage
+                    .iter()
+                    .filter(|e| e.kind == EntryKind::Source || e.kind == EntryKind::Binaries)
+                    .map(|e| e.size)
+                    .sum();
+
+                ui.horizontal(|ui| {
+                    let mut action = |ui: &mut egui::Ui, label: String, plan: &CleanupPlan| {
+                        let enabled = can_delete && !plan.paths.is_empty();
+                        if ui.add_enabled(enabled, egui::Button::new(&label)).clicked() {
+                            request = Some((label, plan.clone()));
+                        }
+                    };
+                    action(
+                        ui,
+                        format!("Delete build artifacts ({})", format_bytes(artifacts.bytes)),
+                        &artifacts,
+                    );
+                    action(
+                        ui,
+                        format!("Delete sources ({})", format_bytes(sources.bytes)),
+                        &sources,
+                    );
+                    action(
+                        ui,
+                        format!(
+                            "Delete unused for {} days ({})",
+                            self.storage_max_age_days,
+                            format_bytes(old.bytes)
+                        ),
+                        &old,
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut self.storage_max_age_days)
+                            .range(1..=3650)
+                            .suffix(" d"),
+                    );
+                });
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Total {}  ·  reclaimable without losing binaries: {}",
+                        format_bytes(total),
+                        format_bytes(sources.bytes)
+                    ))
+                    .small()
+                    .color(pal::LABEL_MUTED),
+                );
+
+                if let Some((label, plan)) = &self.storage_pending {
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            pal::DANGER,
+                            format!(
+                                "{label}: permanently delete {} folder(s)?",
+                                plan.paths.len()
+                            ),
+                        );
+                        if ui
+                            .add_enabled(can_delete, accent_button("Delete"))
+                            .clicked()
+                        {
+                            confirmed = Some(plan.clone());
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    });
+                }
+
+                ui.add_space(6.0);
+                ui.separator();
+
+                if self.storage.is_empty() {
+                    ui.add_space(8.0);
+                    let text = if self.storage_scanning {
+                        "Scanning…"
+                    } else {
+                        "Nothing in the build directory yet."
+                    };
+                    ui.label(egui::RichText::new(text).color(pal::LABEL_MUTED));
+                    return;
+                }
+
+                // ── Entries ───────────────────────────────────────────────────
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::Grid::new("storage_grid")
+                            .num_columns(5)
+                            .striped(true)
+                            .spacing([14.0, 6.0])
+                            .show(ui, |ui| {
+                                for heading in ["Folder", "Kind", "Size", "Last used (UTC)", ""] {
+                                    ui.label(egui::RichText::new(heading).strong());
+                                }
+                                ui.end_row();
+
+                                for entry in &self.storage {
+                                    let name = if entry.kind.is_artifact() {
+                                        format!("  └ {}", entry.name)
+                                    } else {
+                                        entry.name.clone()
+                                    };
+                                    ui.label(egui::RichText::new(name).monospace());
+                                    ui.label(entry.kind.label());
+                                    ui.label(format_bytes(entry.size));
+                                    ui.label(format_utc(entry.last_used));
+                                    if ui
+                                        .add_enabled(
+                                            can_delete && !storage::is_in_use(entry, &in_use),
+                                            egui::Button::new("Delete"),
+                                        )
+                                        .on_disabled_hover_text(
+                                            "Busy, or a running node uses these binaries",
+                                        )
+                                        .clicked()
+                                    {
+                                        request = Some((
+                                            format!("Delete {}", entry.name),
+                                            CleanupPlan {
+                                                paths: vec![entry.path.clone()],
+                                                bytes: entry.size,
+                                            },
+                                        ));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        self.show_storage = open;
+        if request.is_some() {
+            self.storage_pending = request;
+        } else if cancel {
+            self.storage_pending = None;
+        }
+        if let Some(plan) = confirmed {
+            self.storage_pending = None;
+            self.spawn_storage_cleanup(plan);
+        } else if rescan {
+            self.spawn_storage_scan();
+        }
+    }
+
+    // ─── Build log terminal ───────────────────────────────────────────────────
+
+    /// Search box, match navigation and line filter above the terminal.
+    fn render_log_toolbar(&mut self, ui: &mut egui::Ui) {
+        self.refresh_log_index();
+        let match_count = self.log_index.match_count();
+        let filter_before = self.log_filter;
+        let collapsed_before = self.collapsed_commands.len();
+
+        ui.horizontal(|ui| {
+            let search = ui.add(
+                egui::TextEdit::singleline(&mut self.log_search)
+                    .hint_text("Search log…")
+                    .desired_width(220.0),
+            );
+            if search.changed() {
+                self.log_match_index = 0;
+                self.log_scroll_to_match = true;
+                self.log_index_dirty = true;
+            }
+            let enter = search.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            let can_step = match_count > 0;
+            if ui.add_enabled(can_step, egui::Button::new("◀")).clicked() {
+                self.log_match_index = self
+                    .log_match_index
+                    .checked_sub(1)
+                    .unwrap_or(match_count.saturating_sub(1));
+                self.log_scroll_to_match = true;
+            }
+            if ui.add_enabled(can_step, egui::Button::new("▶")).clicked() || (enter && can_step) {
+                self.log_match_index = (self.log_match_index + 1) % match_count;
+                self.log_scroll_to_match = true;
+            }
+            if enter {
+                search.request_focus();
+            }
+
+            if !self.log_search.is_empty() {
+                let count = if match_count == 0 {
+                    "No matches".to_owned()
+                } else {
+                    format!("{} / {match_count}", self.log_match_index + 1)
+                };
+                ui.label(egui::RichText::new(count).small().color(pal::LABEL_MUTED));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("⊞")
+                    .on_hover_text("Expand all command output")
+                    .clicked()
+                {
+                    self.collapsed_commands.clear();
+                }
+                if ui
+                    .small_button("⊟")
+                    .on_hover_text("Collapse all command output under its $ header")
+                    .clicked()
+                {
+                    self.collapsed_commands = self
+                        .log_lines
+                        .iter()
+                        .filter(|l| l.is_command_header())
+                        .filter_map(|l| l.command_id)
+                        .collect();
+                }
+                ui.checkbox(&mut self.log_show_timestamps, "Times");
+                egui::ComboBox::from_id_source("log_filter_combo")
+                    .selected_text(self.log_filter.label())
+                    .width(140.0)
+                    .show_ui(ui, |ui: &mut egui::Ui| {
+                        for f in LogFilter::ALL {
+                            ui.selectable_value(&mut self.log_filter, f, f.label());
+                        }
+                    });
+                ui.label(egui::RichText::new("Show").color(pal::LABEL_MUTED));
+            });
+        });
+
+        if self.log_filter != filter_before || self.collapsed_commands.len() != collapsed_before {
+            self.log_index_dirty = true;
+        }
+    }
+
+    fn refresh_log_index(&mut self) {
+        if self.log_index_dirty {
+            self.log_index = LogIndex::build(
+                &self.log_lines,
+                self.log_filter,
+                &self.collapsed_commands,
+                &self.log_search,
+            );
+            self.log_index_dirty = false;
+        }
+    }
+
+    /// Lay out one terminal row: render ANSI colours, fall back to the
+    /// error / warning class (or stream) colour for uncoloured text, and
+    /// highlight search matches.
+    fn build_row_job(&self, line: &LogLine, row: usize) -> egui::text::LayoutJob {
+        let font_id = egui::FontId::monospace(TERMINAL_FONT_SIZE);
+        let plain_format = |color| egui::text::TextFormat {
+            font_id: font_id.clone(),
+            color,
+            ..Default::default()
+        };
+        let mut job = egui::text::LayoutJob::default();
+
+        if self.log_show_timestamps {
+            let stamp = format!("[{}] ", format_utc_time(line.timestamp));
+            job.append(&stamp, 0.0, plain_format(pal::TERM_TIMESTAMP));
+        }
+        if let (true, Some(id)) = (line.is_command_header(), line.command_id) {
+            let fold = if self.collapsed_commands.contains(&id) {
+                "▶ "
+            } else {
+                "▼ "
+            };
+            job.append(fold, 0.0, plain_format(pal::TERM_COMMAND));
+        }
+
+        let kind_color = match (line.kind, line.stream) {
+            (LineKind::Error, _) => pal::TERM_ERROR,
+            (LineKind::Warning, _) => pal::TERM_WARNING,
+            (LineKind::Normal, LogStream::Command) => pal::TERM_COMMAND,
+            (LineKind::Normal, LogStream::Stderr) => pal::TERM_STDERR,
+            (LineKind::Normal, _) => pal::TERM_TEXT,
+        };
+        let matches = find_matches(&line.plain, &self.log_search);
+        let match_base = self.log_index.matches_before(row);
+
+        let mut span_start = 0;
+        for (text, span_style) in &line.spans {
+            let span_end = span_start + text.len();
+            let mut pos = span_start;
+            while pos < span_end {
+                // Next piece: the rest of a match we are inside, the plain
+                // text up to the next match, or the rest of the span.
+                let next = matches.iter().enumerate().find(|(_, (_, e))| *e > pos);
+                let (piece_end, current) = match next {
+                    Some((i, &(s, e))) if s <= pos => (
+                        e.min(span_end),
+                        Some(match_base + i == self.log_match_index),
+                    ),
+                    Some((_, &(s, _))) => (s.min(span_end), None),
+                    None => (span_end, None),
+                };
+
+                let mut color = span_style.fg.unwrap_or(kind_color);
+                if span_style.dim {
+                    color = color.gamma_multiply(0.6);
+                }
+                let background = match current {
+                    Some(true) => pal::TERM_MATCH_CURRENT_BG,
+                    Some(false) => pal::TERM_MATCH_BG,
+                    None => span_style.bg.unwrap_or(egui::Color32::TRANSPARENT),
+                };
+                job.append(
+                    &line.plain[pos..piece_end],
+                    0.0,
+                    egui::text::TextFormat {
+                        font_id: font_id.clone(),
+                        color,
+                        background,
+                        italics: span_style.italic,
+                        underline: if span_style.underline {
+                            egui::Stroke::new(1.0, color)
+                        } else {
+                            egui::Stroke::NONE
+                        },
+                        ..Default::default()
+                    },
+                );
+                pos = piece_end;
+            }
+            span_start = span_end;
+        }
+
+        job
+    }
+
+    fn render_terminal(&mut self, ui: &mut egui::Ui) {
+        self.refresh_log_index();
+        let row_height = ui.fonts(|f| f.row_height(&egui::FontId::monospace(TERMINAL_FONT_SIZE)));
+        let scroll_to_row = if self.log_scroll_to_match {
+            self.log_scroll_to_match = false;
+            self.log_index.match_row(self.log_match_index)
+        } else {
+            None
+        };
+        let mut toggle: Option<u64> = None;
+
+        egui::Frame {
+            fill: pal::TERM_BG,
+            stroke: egui::Stroke::new(1.0, pal::TERM_BORDER),
+            inner_margin: egui::Margin::same(10.0),
+            rounding: egui::Rounding::same(8.0),
+            outer_margin: egui::Margin::ZERO,
+            ..Default::default()
+        }
+        .show(ui, |ui| {
+            // Hard-pin both min and max to the same value so egui never
+            // allocates more or less space as log content grows.
+            ui.set_min_height(TERMINAL_HEIGHT);
+            ui.set_max_height(TERMINAL_HEIGHT);
+            // Rows must be exactly `row_height` apart for `show_rows`.
+            ui.spacing_mut().item_spacing.y = 0.0;
+
+            let mut area = egui::ScrollArea::both()
+                .id_source("build_log")
+                .stick_to_bottom(true)
+                .max_height(TERMINAL_HEIGHT)
+                .min_scrolled_height(TERMINAL_HEIGHT)
+                .auto_shrink([false, false]);
+            if let Some(row) = scroll_to_row {
+                #[allow(clippy::cast_precision_loss)]
+                let offset = row as f32 * row_height - TERMINAL_HEIGHT / 2.0;
+                area = area.vertical_scroll_offset(offset.max(0.0));
+            }
+
+            // Only the rows in view are laid out, however long the log is.
+            area.show_rows(ui, row_height, self.log_index.row_count(), |ui, rows| {
+                for row in rows {
+                    let line = &self.log_lines[self.log_index.line(row)];
+                    let header = line.command_id.filter(|_| line.is_command_header());
+                    let label = egui::Label::new(self.build_row_job(line, row))
+                        .wrap_mode(egui::TextWrapMode::Extend);
+                    if let Some(id) = header {
+                        // Clicking a `$ cmd` header folds / unfolds its output.
+                        let response = ui
+                            .add(label.sense(egui::Sense::click()))
+                            .on_hover_cursor(egui::CursorIcon::PointingHand);
+                        if response.clicked() {
+                            toggle = Some(id);
+                        }
+                    } else {
+                        ui.add(label);
+                    }
+                }
+            });
+        });
+
+        if let Some(id) = toggle {
+            if !self.collapsed_commands.remove(&id) {
+                self.collapsed_commands.insert(id);
+            }
+            self.log_index_dirty = true;
+        }
+    }
+
+    /// "Recommended: N" next to the cores field, with a button to apply it.
+    fn render_job_advice(&mut self, ui: &mut egui::Ui) {
+        let advice = self.job_advice;
+        let color = if self.cores > advice.jobs {
+            pal::DANGER
+        } else {
+            pal::LABEL_MUTED
+        };
+        let reason = if advice.memory_bound {
+            format!(
+                "{} of memory available; this build needs about {} per job.",
+                format_bytes(advice.memory_available),
+                format_bytes(advice.per_job)
+            )
+        } else {
+            "One core is left free to keep the system responsive.".to_owned()
+        };
         ui.label(
-            egui::RichText::new("Build Log")
-                .strong()
-                .color(pal::TEXT_PRIMARY),
-        );
-        ui.add_space(4.0);
+            egui::RichText::new(format!("· recommended {}", advice.jobs))
+                .small()
+                .color(color),
+        )
+        .on_hover_text(reason);
+        if self.cores != advice.jobs && ui.small_button("Use").clicked() {
+            self.cores = advice.jobs;
+        }
+    }
+
+    // ─── Content renderer (called inside centred column) ──────────────────────
+
+    #[allow(clippy::too_many_lines)]
+    fn render_content(&mut self, ui: &mut egui::Ui) {
+        // Header
+        ui.vertical_centered(|ui| {
+            ui.label(
+                egui::RichText::new("⚙  BitForge")
+                    .size(26.0)
+                    .strong()
+                    .color(pal::TEXT_PRIMARY),
+            );
+            ui.add_space(2.0);
+            ui.label(
+                egui::RichText::new("Bitcoin Core & Electrs Compiler for macOS")
+                    .size(13.0)
+                    .color(pal::LABEL_MUTED),
+            );
+        });
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            if ui.button("🕘  History").clicked() {
+                self.show_history = !self.show_history;
+            }
+            if ui
+                .button("📝  Configs")
+                .on_hover_text(
+                    "Generate bitcoin.conf and electrs config.toml for the selected versions",
+                )
+                .clicked()
+            {
+                self.show_config = !self.show_config;
+                if self.show_config {
+                    self.spawn_config_detect();
+                }
+            }
+            if ui
+                .button("🔀  Compare Options")
+                .on_hover_text("Options added, removed or changed between two built versions")
+                .clicked()
+            {
+                self.show_compare = !self.show_compare;
+                if self.show_compare {
+                    self.load_compare_catalogs();
+                }
+            }
+            if ui
+                .button("🖥  Nodes")
+                .on_hover_text("Start, stop and watch bitcoind and electrs from your builds")
+                .clicked()
+            {
+                self.show_nodes = !self.show_nodes;
+                if self.show_nodes {
+                    self.refresh_node_versions();
+                }
+            }
+            if ui
+                .button("📦  Install")
+                .on_hover_text("Put built binaries on your PATH and switch versions")
+                .clicked()
+            {
+                self.show_install = !self.show_install;
+                if self.show_install {
+                    self.refresh_install();
+                }
+            }
+            if ui
+                .button("💾  Storage")
+                .on_hover_text("Disk space used by sources, build artifacts and binaries")
+                .clicked()
+            {
+                self.show_storage = !self.show_storage;
+                if self.show_storage {
+                    self.spawn_storage_scan();
+                }
+            }
+            if ui
+                .button("🩺  Export Diagnostics")
+                .on_hover_text("Bundle the log, environment, tool versions and CMake files")
+                .clicked()
+            {
+                self.spawn_export_diagnostics();
+            }
+        });
 
-        egui::Frame {
-            fill: pal::TERM_BG,
-            stroke: egui::Stroke::new(1.0, pal::TERM_BORDER),
-            inner_margin: egui::Margin::same(10.0),
-            rounding: egui::Rounding::same(8.0),
-            outer_margin: egui::Margin::ZERO,
-            ..Default::default()
-        }
-        .show(ui, |ui| {
-            // Hard-pin both min and max to the same value so egui never
-            // allocates more or less space as log content grows.
-            ui.set_min_height(TERMINAL_HEIGHT);
-            ui.set_max_height(TERMINAL_HEIGHT);
+        ui.add_space(20.0);
 
-            egui::ScrollArea::vertical()
-                .id_source("build_log")
-                .stick_to_bottom(true)
-                .max_height(TERMINAL_HEIGHT)
-                .min_scrolled_height(TERMINAL_HEIGHT)
-                .auto_shrink([false, false])
+        // ── Step 1 ────────────────────────────────────────────────────────────
+        section_card(ui, "Step 1 — Check & Install Dependencies", |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(
+                        "Scans for required Homebrew packages and the Rust toolchain.",
+                    )
+                    .size(12.5)
+                    .color(pal::LABEL_MUTED),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(!self.is_busy, accent_button("Check & Install"))
+                        .clicked()
+                    {
+                        self.spawn_check_deps();
+                    }
+                });
+            });
+        });
+
+        ui.add_space(10.0);
+
+        // ── Step 2 ────────────────────────────────────────────────────────────
+        section_card(ui, "Step 2 — Configure Build", |ui| {
+            egui::Grid::new("settings_grid")
+                .num_columns(4)
+                .spacing([14.0, 10.0])
+                .show(ui, |ui| {
+                    // Row 1: Target + Cores
+                    ui.label(egui::RichText::new("Target").color(pal::LABEL_MUTED));
+                    let target_before = self.target.clone();
+                    egui::ComboBox::from_id_source("target_combo")
+                        .selected_text(&self.target)
+                        .width(140.0)
+                        .show_ui(ui, |ui: &mut egui::Ui| {
+                            for opt in &["Bitcoin", "Electrs", "Both"] {
+                                ui.selectable_value(&mut self.target, opt.to_string(), *opt);
+                            }
+                        });
+                    if self.target != target_before {
+                        self.job_advice = recommend_jobs(&self.target, self.max_cores);
+                    }
+
+                    ui.label(egui::RichText::new("CPU Cores").color(pal::LABEL_MUTED));
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut self.cores)
+                                .range(1..=self.max_cores)
+                                .speed(1.0),
+                        );
+                        ui.label(
+                            egui::RichText::new(format!("of {}", self.max_cores))
+                                .small()
+                                .color(pal::LABEL_MUTED),
+                        );
+                        self.render_job_advice(ui);
+                    });
+                    ui.end_row();
+
+                    // Row 2: Build directory
+                    ui.label(egui::RichText::new("Output Dir").color(pal::LABEL_MUTED));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.build_dir)
+                            .desired_width(440.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    ui.label(""); // spacer
+                    if ui.button("Browse…").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            self.build_dir = folder.to_string_lossy().into_owned();
+                        }
+                    }
+                    ui.end_row();
+
+                    // Row 3: Signing key
+                    ui.label(egui::RichText::new("Signing Key").color(pal::LABEL_MUTED));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.signing_key)
+                            .desired_width(440.0)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("gpg key ID or email — leave empty to skip signing"),
+                    )
+                    .on_hover_text(
+                        "Signs SHA256SUMS of each build's output folder and of packages; \
+                         Verify only trusts signatures by this key",
+                    );
+                    ui.end_row();
+                });
+            ui.checkbox(
+                &mut self.retry_oom,
+                "Retry with half the cores if the compiler runs out of memory",
+            );
+        });
+
+        ui.add_space(10.0);
+
+        // ── Step 3 ────────────────────────────────────────────────────────────
+        section_card(ui, "Step 3 — Select Versions", |ui| {
+            egui::Grid::new("versions_grid")
+                .num_columns(4)
+                .spacing([14.0, 10.0])
                 .show(ui, |ui| {
-                    ui.set_width(ui.available_width());
+                    ui.label(egui::RichText::new("Bitcoin Core").color(pal::LABEL_MUTED));
+                    egui::ComboBox::from_id_source("bitcoin_combo")
+                        .selected_text(&self.selected_bitcoin)
+                        .width(200.0)
+                        .show_ui(ui, |ui: &mut egui::Ui| {
+                            for v in &self.bitcoin_versions {
+                                ui.selectable_value(
+                                    &mut self.selected_bitcoin,
+                                    v.clone(),
+                                    v.as_str(),
+                                );
+                            }
+                        });
+                    if ui.button("↻  Refresh").clicked() {
+                        self.spawn_refresh_bitcoin_versions();
+                    }
+                    ui.label("");
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new("Electrs").color(pal::LABEL_MUTED));
+                    egui::ComboBox::from_id_source("electrs_combo")
+                        .selected_text(&self.selected_electrs)
+                        .width(200.0)
+                        .show_ui(ui, |ui: &mut egui::Ui| {
+                            for v in &self.electrs_versions {
+                                ui.selectable_value(
+                                    &mut self.selected_electrs,
+                                    v.clone(),
+                                    v.as_str(),
+                                );
+                            }
+                        });
+                    if ui.button("↻  Refresh").clicked() {
+                        self.spawn_refresh_electrs_versions();
+                    }
+                    ui.label("");
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(10.0);
+
+        // ── Progress ──────────────────────────────────────────────────────────
+        section_card(ui, "Build Progress", |ui| {
+            let label = if self.is_busy {
+                format!("{:.0}%", self.progress * 100.0)
+            } else if self.progress >= 1.0 {
+                "Complete".to_owned()
+            } else {
+                "Idle".to_owned()
+            };
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::ProgressBar::new(self.progress)
+                        .desired_width(ui.available_width() - 56.0)
+                        .animate(self.is_busy)
+                        .text(""),
+                );
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new(label).small().color(pal::LABEL_MUTED));
+            });
+        });
+
+        ui.add_space(10.0);
+
+        // ── Build log terminal — FIXED HEIGHT, never resizes ──────────────────
+        ui.horizontal(|ui| {
+            if ui
+                .selectable_label(
+                    self.terminal_tab.is_none(),
+                    egui::RichText::new("Build Log")
+                        .strong()
+                        .color(pal::TEXT_PRIMARY),
+                )
+                .clicked()
+            {
+                self.terminal_tab = None;
+            }
+            for kind in NodeKind::ALL {
+                let running = self.nodes.get(&kind).is_some_and(|c| c.running.is_some());
+                let tab = format!("{} {}", if running { "●" } else { "○" }, kind.binary());
+                if ui
+                    .selectable_label(self.terminal_tab == Some(kind), tab)
+                    .clicked()
+                {
+                    self.terminal_tab = Some(kind);
+                }
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let log_path = match self.terminal_tab {
+                    None => self.last_log_file.clone(),
+                    Some(kind) => self
+                        .nodes
+                        .get(&kind)
+                        .and_then(|c| c.running.as_ref())
+                        .map(|r| r.log_file.clone()),
+                };
+                let has_log = log_path.is_some();
+                if ui
+                    .add_enabled(has_log, egui::Button::new("Copy log path"))
+                    .clicked()
+                {
+                    if let Some(path) = &log_path {
+                        ui.output_mut(|o| o.copied_text = path.to_string_lossy().into_owned());
+                    }
+                }
+                if ui
+                    .add_enabled(has_log, egui::Button::new("Open log file"))
+                    .clicked()
+                {
+                    if let Some(path) = &log_path {
+                        if let Err(e) = open_path(path) {
+                            self.modal = Some(Modal::Alert {
+                                title: "Could Not Open".into(),
+                                message: format!("Failed to open {}:\n{e}", path.display()),
+                                is_error: true,
+                            });
+                        }
+                    }
+                }
+                if let Some(path) = &log_path {
                     ui.label(
-                        egui::RichText::new(&self.log_buffer)
-                            .color(pal::TERM_TEXT)
+                        egui::RichText::new(path.to_string_lossy())
+                            .small()
                             .monospace()
-                            .size(11.5),
+                            .color(pal::LABEL_MUTED),
                     );
-                });
+                }
+            });
         });
+        ui.add_space(4.0);
+
+        if let Some(kind) = self.terminal_tab {
+            self.render_node_terminal(ui, kind);
+        } else {
+            self.render_log_toolbar(ui);
+            ui.add_space(4.0);
+            self.render_terminal(ui);
+        }
 
         ui.add_space(18.0);
 
@@ -822,12 +3173,243 @@ impl BitForgeApp {
             {
                 self.spawn_compile();
             }
+            ui.add_space(4.0);
+            if ui
+                .add_enabled(!self.is_busy, egui::Button::new("🧪  Validate on regtest"))
+                .on_hover_text(
+                    "Run the selected bitcoind and electrs builds together on a \
+                     throwaway regtest chain and query electrs over the Electrum protocol",
+                )
+                .clicked()
+            {
+                self.spawn_validate();
+            }
         });
     }
 }
 
+/// Bitcoin Core's default data directory on this platform.
+fn default_bitcoin_datadir() -> String {
+    let Some(home) = home_dir() else {
+        return String::new();
+    };
+    let dir = if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/Bitcoin")
+    } else {
+        home.join(".bitcoin")
+    };
+    dir.to_string_lossy().into_owned()
+}
+
+// ─── Build history helpers ────────────────────────────────────────────────────
+
+/// How each project of a build is run.
+struct BuildOptions<'a> {
+    cores: usize,
+    /// Retry at half the jobs after an out-of-memory kill.
+    retry_oom: bool,
+    /// gpg key that signs the output folder's SHA256SUMS.
+    signing_key: Option<&'a str>,
+}
+
+/// Build one project (`"Bitcoin"` or `"Electrs"`) with `opts.cores` jobs.
+/// With `retry_oom`, a build the diagnosis blames on an out-of-memory kill is
+/// retried at half the jobs until it succeeds or runs single-threaded.  With
+/// a `signing_key`, the output is signed before the build counts as
+/// succeeded; a signing failure is a failed build.  Every attempt gets its
+/// own log file and history record.  On final failure the "Compilation
+/// Failed" dialog is sent and `None` returned.
+async fn build_project(
+    target: &str,
+    version: &str,
+    build_dir: &Path,
+    opts: &BuildOptions<'_>,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Option<PathBuf> {
+    let mut cores = opts.cores;
+    let is_bitcoin = target == "Bitcoin";
+    let (project, flags) = if is_bitcoin {
+        ("bitcoin", BITCOIN_CMAKE_FLAGS)
+    } else {
+        ("electrs", ELECTRS_CARGO_FLAGS)
+    };
+
+    loop {
+        let started_at = unix_now();
+        let log_file = start_log_file(build_dir, project, version, started_at, tx).await;
+        let outcome = if is_bitcoin {
+            compile_bitcoin(version, build_dir, cores, env, tx).await
+        } else {
+            compile_electrs(version, build_dir, cores, env, tx).await
+        };
+        let outcome = match (outcome, opts.signing_key) {
+            (Ok(dir), Some(key)) => signing::sign(&dir, key, env, tx)
+                .await
+                .map(|()| dir)
+                .map_err(|e| {
+                    anyhow::anyhow!("The build succeeded, but signing its SHA256SUMS failed: {e:#}")
+                }),
+            (outcome, _) => outcome,
+        };
+        let mut record = build_record(
+            target, version, flags, cores, build_dir, started_at, &outcome,
+        );
+        let retry = opts.retry_oom && cores > 1 && record.diagnoses.iter().any(|id| id == OOM_KILL);
+
+        let output = match outcome {
+            Ok(dir) => Some(dir),
+            Err(e) if retry => {
+                log_msg(
+                    tx,
+                    &format!(
+                        "\n❌ {e}\n🔁 Out of memory with {cores} jobs — retrying with {}...\n",
+                        (cores / 2).max(1)
+                    ),
+                );
+                None
+            }
+            Err(e) => {
+                tx.send(AppMessage::ShowDialog {
+                    title: "Compilation Failed".into(),
+                    message: report_failure(&e, log_file.as_deref(), tx),
+                    is_error: true,
+                })
+                .ok();
+                None
+            }
+        };
+        tx.send(AppMessage::LogFileFinished).ok();
+        record.log_file = log_file;
+        save_record(record, tx).await;
+
+        if !retry {
+            return output;
+        }
+        cores = (cores / 2).max(1);
+    }
+}
+
+/// Snapshot one finished project build as a history record.
+fn build_record(
+    target: &str,
+    version: &str,
+    flags: &[&str],
+    cores: usize,
+    build_dir: &Path,
+    started_at: u64,
+    outcome: &anyhow::Result<PathBuf>,
+) -> BuildRecord {
+    let (result, error, diagnoses, output_dir) = match outcome {
+        Ok(dir) => (BuildResult::Succeeded, None, Vec::new(), Some(dir.clone())),
+        Err(e) => (
+            BuildResult::Failed,
+            Some(format!("{e:#}")),
+            diagnose(e)
+                .iter()
+                .map(|d| d.signature.id.to_owned())
+                .collect(),
+            None,
+        ),
+    };
+    BuildRecord {
+        target: target.to_owned(),
+        version: version.to_owned(),
+        flags: flags.iter().map(ToString::to_string).collect(),
+        cores,
+        started_at,
+        finished_at: unix_now(),
+        result,
+        error,
+        diagnoses,
+        build_dir: build_dir.to_path_buf(),
+        output_dir,
+        log_file: None,
+    }
+}
+
+/// Create `<build_dir>/logs/<project>-<version>-<timestamp>.log` and tell the
+/// UI to tee the log into it.  Returns `None` (after logging why) if the logs
+/// directory can't be created — a missing log file never blocks a build.
+async fn start_log_file(
+    build_dir: &Path,
+    project: &str,
+    version: &str,
+    started_at: u64,
+    tx: &UiSender<AppMessage>,
+) -> Option<PathBuf> {
+    let logs_dir = build_dir.join("logs");
+    if let Err(e) = tokio::fs::create_dir_all(&logs_dir).await {
+        log_msg(
+            tx,
+            &format!("⚠️  Could not create {}: {e}\n", logs_dir.display()),
+        );
+        return None;
+    }
+    let path = logs_dir.join(format!(
+        "{project}-{}-{}.log",
+        version.trim_start_matches('v'),
+        format_utc_compact(started_at)
+    ));
+    tx.send(AppMessage::LogFileStarted(path.clone())).ok();
+    log_msg(tx, &format!("📝 Full log: {}\n", path.display()));
+    Some(path)
+}
+
+/// Log the failure, run diagnosis over the failed step's output, and return
+/// the body of the "Compilation Failed" dialog: the error, the matched
+/// causes and fixes, and where to find the complete log.
+fn report_failure(
+    err: &anyhow::Error,
+    log_file: Option<&Path>,
+    tx: &UiSender<AppMessage>,
+) -> String {
+    log_msg(tx, &format!("\n❌ Compilation failed: {err}\n"));
+
+    let mut message = err.to_string();
+    let diagnoses = diagnose(err);
+    if diagnoses.is_empty() {
+        log_msg(
+            tx,
+            "🔎 No known failure signature matched — see the log above.\n",
+        );
+    } else {
+        let report = format_report(&diagnoses);
+        log_msg(tx, &format!("\n{report}"));
+        message.push_str("\n\n");
+        message.push_str(&report);
+    }
+    if let Some(path) = log_file {
+        message.push_str(&format!("\nFull log:\n{}", path.display()));
+    }
+    message
+}
+
+/// Persist `record` and hand it to the UI's history list.
+/// A failed write is logged but never fails the build itself.
+async fn save_record(record: BuildRecord, tx: &UiSender<AppMessage>) {
+    if let Err(e) = history::append(&record).await {
+        log_msg(tx, &format!("⚠️  Could not save build history: {e:#}\n"));
+    }
+    tx.send(AppMessage::BuildRecorded(record)).ok();
+}
+
 // ─── UI helpers ───────────────────────────────────────────────────────────────
 
+/// Open a file or folder with the system default handler (Finder on macOS).
+/// The opener is reaped on a detached thread so it does not linger as a
+/// zombie.
+fn open_path(path: &Path) -> std::io::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut child = std::process::Command::new(opener).arg(path).spawn()?;
+    std::thread::spawn(move || child.wait().ok());
+    Ok(())
+}
+
 /// macOS-style filled accent button.
 fn accent_button(label: &str) -> egui::Button<'_> {
     egui::Button::new(
@@ -870,6 +3452,12 @@ impl eframe::App for BitForgeApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.drain_messages();
         self.render_modal(ctx);
+        self.render_history_window(ctx);
+        self.render_storage_window(ctx);
+        self.render_install_window(ctx);
+        self.render_config_window(ctx);
+        self.render_compare_window(ctx);
+        self.render_nodes_window(ctx);
 
         // ── Status bar ────────────────────────────────────────────────────────
         egui::TopBottomPanel::bottom("status_bar")
@@ -880,11 +3468,27 @@ impl eframe::App for BitForgeApp {
                 ..Default::default()
             })
             .show(ctx, |ui| {
-                ui.label(
-                    egui::RichText::new(&self.status_bar)
-                        .small()
-                        .color(pal::LABEL_MUTED),
-                );
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(&self.status_bar)
+                            .small()
+                            .color(pal::LABEL_MUTED),
+                    );
+                    if let Some(stats) = &self.system_stats {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let color = if stats.under_pressure() {
+                                pal::DANGER
+                            } else {
+                                pal::LABEL_MUTED
+                            };
+                            ui.label(egui::RichText::new(stats.summary()).small().color(color))
+                                .on_hover_text(
+                                    "System load while building. Memory turns red when less \
+                                     than 10% of RAM is available.",
+                                );
+                        });
+                    }
+                });
             });
 
         // ── Main window ───────────────────────────────────────────────────────
@@ -913,11 +3517,5 @@ impl eframe::App for BitForgeApp {
                         ui.add_space(28.0);
                     });
             });
-
-        ctx.request_repaint_after(if self.is_busy {
-            std::time::Duration::from_millis(50)
-        } else {
-            std::time::Duration::from_millis(250)
-        });
     }
 }
@@ -1,7 +1,8 @@
 // src/compiler.rs
 //
-// compile_bitcoin  — clone, cmake configure, cmake build, copy binaries.
-// compile_electrs  — clone, cargo build --release, copy binary.
+// compile_bitcoin  — clone, cmake configure, cmake build, copy and verify
+//                    binaries.
+// compile_electrs  — clone, cargo build --release, copy and verify binary.
 //
 // Bitcoin Core v29+ uses CMake exclusively (autotools removed upstream).
 // The critical env requirement: PKG_CONFIG_PATH must point at Homebrew's
@@ -11,17 +12,46 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::Sender;
 
 use anyhow::{Context, Result};
 
-use crate::messages::{log_msg, AppMessage};
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::options;
 use crate::process::{probe, run_command};
+use crate::verify::smoke_test;
 
-const BITCOIN_REPO: &str = "https://github.com/bitcoin/bitcoin.git";
-const ELECTRS_REPO: &str = "https://github.com/romanz/electrs.git";
+pub const BITCOIN_REPO: &str = "https://github.com/bitcoin/bitcoin.git";
+pub const ELECTRS_REPO: &str = "https://github.com/romanz/electrs.git";
 const SEP: &str = "============================================================";
 
+/// CMake configure flags for Bitcoin Core (matching the official
+/// build-osx.md for v29+):
+///   -DENABLE_WALLET=OFF   skip wallet (no Berkeley DB / SQLite needed)
+///   -DENABLE_IPC=OFF      skip IPC (no capnp needed)
+///   -DBUILD_TESTS=OFF     skip test suite compilation
+///   -DBUILD_BENCH=OFF     skip benchmarks
+///   -DBUILD_GUI=OFF       skip Qt GUI
+///   -DWITH_MINIUPNPC=OFF  skip optional UPnP dep
+///   -DWITH_NATPMP=OFF     skip optional NAT-PMP dep
+///   -DWITH_ZMQ=OFF        skip optional ZMQ dep
+///
+/// With wallet/IPC/tests/bench/GUI/optional-deps all disabled, the only
+/// required non-system dependency is libevent, which pkg-config finds
+/// instantly once `PKG_CONFIG_PATH` is set correctly.
+pub const BITCOIN_CMAKE_FLAGS: &[&str] = &[
+    "-DENABLE_WALLET=OFF",
+    "-DENABLE_IPC=OFF",
+    "-DBUILD_TESTS=OFF",
+    "-DBUILD_BENCH=OFF",
+    "-DBUILD_GUI=OFF",
+    "-DWITH_MINIUPNPC=OFF",
+    "-DWITH_NATPMP=OFF",
+    "-DWITH_ZMQ=OFF",
+];
+
+/// Cargo flags for the Electrs build (`--jobs` is added from the UI setting).
+pub const ELECTRS_CARGO_FLAGS: &[&str] = &["--release"];
+
 // ─── Public compile functions ─────────────────────────────────────────────────
 
 pub async fn compile_bitcoin(
@@ -29,7 +59,7 @@ pub async fn compile_bitcoin(
     build_dir: &Path,
     cores: usize,
     env: &HashMap<String, String>,
-    tx: &Sender<AppMessage>,
+    tx: &UiSender<AppMessage>,
 ) -> Result<PathBuf> {
     log_msg(
         tx,
@@ -57,20 +87,7 @@ pub async fn compile_bitcoin(
     clone_or_update(&src_dir, build_dir, version, BITCOIN_REPO, tx, &env).await?;
 
     // ── Step 2: cmake configure ───────────────────────────────────────────────
-    //
-    // Flags used (matching the official build-osx.md for v29+):
-    //   -DENABLE_WALLET=OFF   skip wallet (no Berkeley DB / SQLite needed)
-    //   -DENABLE_IPC=OFF      skip IPC (no capnp needed)
-    //   -DBUILD_TESTS=OFF     skip test suite compilation
-    //   -DBUILD_BENCH=OFF     skip benchmarks
-    //   -DBUILD_GUI=OFF       skip Qt GUI
-    //   -DWITH_MINIUPNPC=OFF  skip optional UPnP dep
-    //   -DWITH_NATPMP=OFF     skip optional NAT-PMP dep
-    //   -DWITH_ZMQ=OFF        skip optional ZMQ dep
-    //
-    // With wallet/IPC/tests/bench/GUI/optional-deps all disabled, the only
-    // required non-system dependency is libevent, which pkg-config finds
-    // instantly once PKG_CONFIG_PATH is set correctly.
+    // See BITCOIN_CMAKE_FLAGS for the flag rationale.
 
     log_msg(
         tx,
@@ -88,27 +105,13 @@ pub async fn compile_bitcoin(
     tx.send(AppMessage::Progress(0.2)).ok();
 
     run_command(
-        "cmake -B build \
-            -DENABLE_WALLET=OFF \
-            -DENABLE_IPC=OFF \
-            -DBUILD_TESTS=OFF \
-            -DBUILD_BENCH=OFF \
-            -DBUILD_GUI=OFF \
-            -DWITH_MINIUPNPC=OFF \
-            -DWITH_NATPMP=OFF \
-            -DWITH_ZMQ=OFF",
+        &format!("cmake -B build {}", BITCOIN_CMAKE_FLAGS.join(" ")),
         Some(&src_dir),
         &env,
         tx,
     )
     .await
-    .context(
-        "cmake configure failed.\n\
-         Common causes:\n\
-         - libevent not installed: brew install libevent\n\
-         - cmake not installed:    brew install cmake\n\
-         - Xcode CLI tools missing: xcode-select --install",
-    )?;
+    .context("cmake configure failed")?;
 
     // ── Step 3: cmake build ───────────────────────────────────────────────────
     log_msg(
@@ -156,6 +159,8 @@ pub async fn compile_bitcoin(
             bin_dir.display()
         ));
     }
+    smoke_test(&copied, version, &env, tx).await?;
+    options::capture(&output_dir, "bitcoind", version, &env, tx).await;
 
     log_msg(
         tx,
@@ -182,7 +187,7 @@ pub async fn compile_electrs(
     build_dir: &Path,
     cores: usize,
     env: &HashMap<String, String>,
-    tx: &Sender<AppMessage>,
+    tx: &UiSender<AppMessage>,
 ) -> Result<PathBuf> {
     log_msg(
         tx,
@@ -230,7 +235,10 @@ pub async fn compile_electrs(
     tx.send(AppMessage::Progress(0.3)).ok();
 
     run_command(
-        &format!("cargo build --release --jobs {cores}"),
+        &format!(
+            "cargo build {} --jobs {cores}",
+            ELECTRS_CARGO_FLAGS.join(" ")
+        ),
         Some(&src_dir),
         &env,
         tx,
@@ -251,7 +259,15 @@ pub async fn compile_electrs(
     let output_dir = build_dir
         .join("binaries")
         .join(format!("electrs-{version_clean}"));
-    copy_binaries(&output_dir, &[binary], tx).await?;
+    let copied = copy_binaries(&output_dir, &[binary], tx).await?;
+    if copied.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Failed to copy the Electrs binary to {}",
+            output_dir.display()
+        ));
+    }
+    smoke_test(&copied, version, &env, tx).await?;
+    options::capture(&output_dir, "electrs", version, &env, tx).await;
 
     log_msg(
         tx,
@@ -272,6 +288,7 @@ pub async fn compile_electrs(
 /// Critical differences from `cargo_env`:
 /// - `PKG_CONFIG_PATH` set → cmake finds Homebrew deps via pkg-config instantly.
 /// - TERM NOT set to "dumb" → cmake streams output in real time, not batched.
+/// - Colour forced on for cmake and clang diagnostics.
 fn bitcoin_env(base: &HashMap<String, String>) -> HashMap<String, String> {
     let mut env = base.clone();
 
@@ -296,11 +313,15 @@ fn bitcoin_env(base: &HashMap<String, String>) -> HashMap<String, String> {
     }
     env.insert("PKG_CONFIG_PATH".to_owned(), pcp.join(":"));
 
-    // Suppress colours but do NOT set TERM=dumb (cmake buffers output when dumb).
+    // Do NOT set TERM=dumb (cmake buffers output when dumb).  Output goes to
+    // a pipe, so colour has to be forced: CLICOLOR_FORCE for cmake's
+    // Makefile progress, CMAKE_COLOR_DIAGNOSTICS (cmake ≥ 3.24) for clang.
+    // The log panel renders the resulting SGR sequences.
     env.remove("TERM");
-    env.insert("NO_COLOR".to_owned(), "1".to_owned());
-    env.insert("CLICOLOR".to_owned(), "0".to_owned());
-    env.insert("CLICOLOR_FORCE".to_owned(), "0".to_owned());
+    env.remove("NO_COLOR");
+    env.insert("CLICOLOR".to_owned(), "1".to_owned());
+    env.insert("CLICOLOR_FORCE".to_owned(), "1".to_owned());
+    env.insert("CMAKE_COLOR_DIAGNOSTICS".to_owned(), "ON".to_owned());
     env.insert("GIT_PROGRESS_DELAY".to_owned(), "0".to_owned());
 
     env
@@ -309,12 +330,14 @@ fn bitcoin_env(base: &HashMap<String, String>) -> HashMap<String, String> {
 /// Environment for Cargo / Rust builds (Electrs).
 fn cargo_env(base: &HashMap<String, String>) -> HashMap<String, String> {
     let mut env = base.clone();
-    env.insert("NO_COLOR".to_owned(), "1".to_owned());
+    // Colour is forced because output goes to a pipe; the log panel renders
+    // cargo's red errors / yellow warnings from the SGR sequences.
+    env.remove("NO_COLOR");
     env.insert("TERM".to_owned(), "dumb".to_owned());
-    env.insert("CLICOLOR".to_owned(), "0".to_owned());
-    env.insert("CLICOLOR_FORCE".to_owned(), "0".to_owned());
+    env.insert("CLICOLOR".to_owned(), "1".to_owned());
+    env.insert("CLICOLOR_FORCE".to_owned(), "1".to_owned());
     env.insert("GIT_PROGRESS_DELAY".to_owned(), "0".to_owned());
-    env.insert("CARGO_TERM_COLOR".to_owned(), "never".to_owned());
+    env.insert("CARGO_TERM_COLOR".to_owned(), "always".to_owned());
     env.insert("CARGO_TERM_PROGRESS_WHEN".to_owned(), "always".to_owned());
     env.insert("CARGO_TERM_PROGRESS_WIDTH".to_owned(), "60".to_owned());
     env
@@ -355,7 +378,7 @@ async fn collect_executables(dir: &Path) -> Vec<PathBuf> {
 async fn copy_binaries(
     dest_dir: &Path,
     binary_files: &[PathBuf],
-    tx: &Sender<AppMessage>,
+    tx: &UiSender<AppMessage>,
 ) -> Result<Vec<PathBuf>> {
     tokio::fs::create_dir_all(dest_dir)
         .await
@@ -418,7 +441,7 @@ async fn clone_or_update(
     build_dir: &Path,
     version: &str,
     repo_url: &str,
-    tx: &Sender<AppMessage>,
+    tx: &UiSender<AppMessage>,
     env: &HashMap<String, String>,
 ) -> Result<()> {
     validate_version_tag(version)?;
@@ -481,7 +504,7 @@ async fn clone_or_update(
 
 // ─── Utilities ────────────────────────────────────────────────────────────────
 
-fn validate_version_tag(tag: &str) -> Result<()> {
+pub fn validate_version_tag(tag: &str) -> Result<()> {
     if tag
         .chars()
         .all(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '_'))
@@ -494,6 +517,7 @@ fn validate_version_tag(tag: &str) -> Result<()> {
     }
 }
 
-fn shell_quote(s: &str) -> String {
+/// Single-quote `s` for `sh -c`, escaping embedded single quotes.
+pub fn shell_quote(s: &str) -> String {
     format!("'{}'", s.replace('\'', r"'\''"))
 }
@@ -0,0 +1,355 @@
+// src/config_gen.rs
+//
+// Generate a `bitcoin.conf` and a matching electrs `config.toml` for the
+// selected builds.
+//
+// Which options are offered comes from the built binaries themselves:
+// `bitcoind -help` lists `-testnet4` only from v28, the `-zmqpub*` options
+// only when built with ZMQ (the default flags turn it off), and so on;
+// `electrs --help` says which networks electrs accepts.
+//
+// RPC credentials use `rpcauth`, hashed exactly like Bitcoin Core's
+// `share/rpcauth/rpcauth.py`: a random 16-byte hex salt, and
+// HMAC-SHA256(key = salt, message = password).  Only the hash goes into
+// `bitcoin.conf`; the password is shown once.  electrs authenticates with
+// the node's cookie file, so it never needs the password.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::process::probe;
+
+const ZMQ_RAWBLOCK_PORT: u16 = 28332;
+const ZMQ_RAWTX_PORT: u16 = 28333;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    pub const ALL: [Self; 5] = [
+        Self::Mainnet,
+        Self::Testnet,
+        Self::Testnet4,
+        Self::Signet,
+        Self::Regtest,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet3",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        }
+    }
+
+    /// The `bitcoin.conf` line selecting this chain.
+    const fn conf_line(self) -> Option<&'static str> {
+        match self {
+            Self::Mainnet => None,
+            Self::Testnet => Some("testnet=1"),
+            Self::Testnet4 => Some("testnet4=1"),
+            Self::Signet => Some("signet=1"),
+            Self::Regtest => Some("regtest=1"),
+        }
+    }
+
+    /// Subdirectory of the datadir holding this chain (and its `.cookie`).
+    const fn datadir_subdir(self) -> &'static str {
+        match self {
+            Self::Mainnet => "",
+            Self::Testnet => "testnet3",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        }
+    }
+
+    /// electrs' `network` value.
+    const fn electrs_name(self) -> &'static str {
+        match self {
+            Self::Mainnet => "bitcoin",
+            Self::Testnet => "testnet",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        }
+    }
+
+    /// Default (RPC, P2P, Electrum) ports.
+    const fn ports(self) -> (u16, u16, u16) {
+        match self {
+            Self::Mainnet => (8332, 8333, 50001),
+            Self::Testnet => (18332, 18333, 60001),
+            Self::Testnet4 => (48332, 48333, 40001),
+            Self::Signet => (38332, 38333, 60601),
+            Self::Regtest => (18443, 18444, 60401),
+        }
+    }
+
+    /// The `bitcoind -help` option that only exists in versions supporting
+    /// this chain; mainnet, testnet3 and regtest are always there.
+    const fn help_option(self) -> Option<&'static str> {
+        match self {
+            Self::Testnet4 => Some("-testnet4"),
+            Self::Signet => Some("-signet"),
+            Self::Mainnet | Self::Testnet | Self::Regtest => None,
+        }
+    }
+}
+
+/// What the selected builds support.  Missing binaries leave everything
+/// off, so the generator only offers what it can confirm.
+#[derive(Clone, Debug, Default)]
+pub struct Capabilities {
+    pub bitcoin_found: bool,
+    pub electrs_found: bool,
+    /// Chains both bitcoind and (if present) electrs accept.
+    pub networks: Vec<Network>,
+    pub prune: bool,
+    pub rpcauth: bool,
+    pub zmq: bool,
+}
+
+/// Ask the built binaries in `bitcoin_dir` / `electrs_dir` what they support.
+pub async fn detect(
+    bitcoin_dir: &Path,
+    electrs_dir: &Path,
+    env: &HashMap<String, String>,
+) -> Capabilities {
+    let bitcoind = bitcoin_dir.join("bitcoind").to_string_lossy().into_owned();
+    let electrs = electrs_dir.join("electrs").to_string_lossy().into_owned();
+    let Some(help) = probe(&[&bitcoind, "-help"], env).await else {
+        return Capabilities::default();
+    };
+    let electrs_help = probe(&[&electrs, "--help"], env).await;
+
+    let has = |option: &str| {
+        help.lines()
+            .any(|l| l.trim_start().split(['=', ' ']).next() == Some(option))
+    };
+    let networks = Network::ALL
+        .into_iter()
+        .filter(|n| n.help_option().is_none_or(has))
+        .filter(|n| {
+            electrs_help
+                .as_deref()
+                .is_none_or(|h| *n == Network::Mainnet || h.contains(n.electrs_name()))
+        })
+        .collect();
+
+    Capabilities {
+        bitcoin_found: true,
+        electrs_found: electrs_help.is_some(),
+        networks,
+        prune: has("-prune"),
+        rpcauth: has("-rpcauth"),
+        zmq: has("-zmqpubrawblock"),
+    }
+}
+
+/// Settings chosen in the Configuration window.
+#[derive(Clone, Debug)]
+pub struct ConfigOptions {
+    pub network: Network,
+    pub datadir: String,
+    /// Prune target in MiB; `None` keeps the full chain (required by electrs).
+    pub prune_mib: Option<u64>,
+    pub rpc_user: String,
+    pub zmq: bool,
+    pub electrs_db_dir: String,
+}
+
+/// Generated files, plus the RPC password that matches the `rpcauth` line.
+#[derive(Clone, Debug)]
+pub struct GeneratedConfig {
+    pub bitcoin_conf: String,
+    pub electrs_toml: Option<String>,
+    pub rpc_password: Option<String>,
+}
+
+// ─── Generation ───────────────────────────────────────────────────────────────
+
+/// Why `user` cannot be an RPC user name, if it can't.  Only `[A-Za-z0-9_.-]`
+/// is allowed: a `:` would split the `rpcauth` value, and a newline or `#`
+/// would inject lines into bitcoin.conf.
+pub fn rpc_user_error(user: &str) -> Option<String> {
+    let bad: String = user
+        .chars()
+        .filter(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+        .collect();
+    (!bad.is_empty()).then(|| {
+        format!("RPC user may only contain letters, digits, '_', '.' and '-' (found {bad:?})")
+    })
+}
+
+/// Why `datadir` cannot go into bitcoin.conf, if it can't: a newline would
+/// inject lines and a `#` would start a comment.
+pub fn datadir_error(datadir: &str) -> Option<String> {
+    datadir
+        .contains(['\n', '\r', '#'])
+        .then(|| "Data directory may not contain a line break or '#'".to_owned())
+}
+
+pub fn generate(
+    opts: &ConfigOptions,
+    caps: &Capabilities,
+    bitcoin_version: &str,
+    electrs_version: &str,
+) -> Result<GeneratedConfig> {
+    let (rpc_port, p2p_port, electrum_port) = opts.network.ports();
+    let mut conf = vec![format!(
+        "# bitcoin.conf for Bitcoin Core {bitcoin_version} ({}), generated by BitForge",
+        opts.network.label()
+    )];
+    if let Some(line) = opts.network.conf_line() {
+        conf.push(line.to_owned());
+    }
+    if let Some(error) = datadir_error(&opts.datadir) {
+        anyhow::bail!(error);
+    }
+    conf.push(format!("datadir={}", opts.datadir));
+    conf.push("server=1".to_owned());
+
+    if let Some(mib) = opts.prune_mib.filter(|_| caps.prune) {
+        conf.push(String::new());
+        conf.push(
+            "# Keep only the most recent blocks (MiB).  electrs needs an unpruned node.".to_owned(),
+        );
+        conf.push(format!("prune={mib}"));
+    }
+
+    let mut rpc_password = None;
+    if caps.rpcauth && !opts.rpc_user.trim().is_empty() {
+        if let Some(error) = rpc_user_error(opts.rpc_user.trim()) {
+            anyhow::bail!(error);
+        }
+        let password = random_hex(32)?;
+        conf.push(String::new());
+        conf.push(format!(
+            "# RPC user \"{}\"; the password is not stored here.",
+            opts.rpc_user.trim()
+        ));
+        conf.push(rpcauth_line(opts.rpc_user.trim(), &password)?);
+        rpc_password = Some(password);
+    }
+
+    if opts.zmq && caps.zmq {
+        conf.push(String::new());
+        conf.push("# ZMQ notifications".to_owned());
+        conf.push(format!(
+            "zmqpubrawblock=tcp://127.0.0.1:{ZMQ_RAWBLOCK_PORT}"
+        ));
+        conf.push(format!("zmqpubrawtx=tcp://127.0.0.1:{ZMQ_RAWTX_PORT}"));
+    }
+
+    let electrs_toml = caps.electrs_found.then(|| {
+        let cookie = Path::new(&opts.datadir)
+            .join(opts.network.datadir_subdir())
+            .join(".cookie");
+        let mut toml = vec![
+            format!(
+                "# electrs config.toml for Electrs {electrs_version} ({}), generated by BitForge",
+                opts.network.label()
+            ),
+            format!("network = \"{}\"", opts.network.electrs_name()),
+            format!("daemon_dir = {}", toml_string(&opts.datadir)),
+            format!("cookie_file = {}", toml_string(&cookie.to_string_lossy())),
+            format!("daemon_rpc_addr = \"127.0.0.1:{rpc_port}\""),
+            format!("daemon_p2p_addr = \"127.0.0.1:{p2p_port}\""),
+            format!("db_dir = {}", toml_string(&opts.electrs_db_dir)),
+            format!("electrum_rpc_addr = \"127.0.0.1:{electrum_port}\""),
+            "log_filters = \"INFO\"".to_owned(),
+        ];
+        if opts.prune_mib.is_some() && caps.prune {
+            toml.insert(
+                1,
+                "# WARNING: bitcoin.conf enables pruning, which electrs does not support."
+                    .to_owned(),
+            );
+        }
+        toml.join("\n") + "\n"
+    });
+
+    Ok(GeneratedConfig {
+        bitcoin_conf: conf.join("\n") + "\n",
+        electrs_toml,
+        rpc_password,
+    })
+}
+
+/// Write `config` to `out_dir` (`bitcoin.conf`, `config.toml`) and return
+/// the paths written.
+pub async fn write(
+    config: &GeneratedConfig,
+    out_dir: &Path,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<PathBuf>> {
+    tokio::fs::create_dir_all(out_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let mut written = Vec::new();
+    let files = [
+        ("bitcoin.conf", Some(&config.bitcoin_conf)),
+        ("config.toml", config.electrs_toml.as_ref()),
+    ];
+    for (name, content) in files {
+        let Some(content) = content else {
+            continue;
+        };
+        let path = out_dir.join(name);
+        tokio::fs::write(&path, content)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                .await
+                .ok();
+        }
+        log_msg(tx, &format!("  ✓ {}\n", path.display()));
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// `rpcauth=<user>:<salt>$<hmac>`, as printed by `rpcauth.py`.
+fn rpcauth_line(user: &str, password: &str) -> Result<String> {
+    let salt = random_hex(16)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(salt.as_bytes()).context("Invalid HMAC key")?;
+    mac.update(password.as_bytes());
+    let hash = to_hex(&mac.finalize().into_bytes());
+    Ok(format!("rpcauth={user}:{salt}${hash}"))
+}
+
+fn random_hex(bytes: usize) -> Result<String> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::getrandom(&mut buf).context("No system randomness available")?;
+    Ok(to_hex(&buf))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+/// A TOML basic string.
+fn toml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -3,12 +3,11 @@
 // Background task: check and optionally install all build dependencies.
 
 use std::collections::HashMap;
-use std::sync::mpsc::Sender;
 
 use anyhow::Result;
 use tokio::sync::oneshot;
 
-use crate::messages::{log_msg, AppMessage, ConfirmRequest};
+use crate::messages::{log_msg, AppMessage, ConfirmRequest, UiSender};
 use crate::process::{probe, run_command};
 
 // Homebrew packages required for Bitcoin Core (autotools + cmake) and Electrs.
@@ -37,8 +36,8 @@ const BREW_PACKAGES: &[&str] = &[
 pub async fn check_dependencies_task(
     brew: String,
     env: HashMap<String, String>,
-    log_tx: Sender<AppMessage>,
-    confirm_tx: Sender<ConfirmRequest>,
+    log_tx: UiSender<AppMessage>,
+    confirm_tx: UiSender<ConfirmRequest>,
 ) -> Result<bool> {
     log_msg(&log_tx, "\n=== Checking System Dependencies ===\n");
     log_msg(&log_tx, &format!("✓ Homebrew found at: {brew}\n"));
@@ -206,7 +205,7 @@ pub async fn check_dependencies_task(
 async fn check_rust_installation(
     brew: &str,
     env: &HashMap<String, String>,
-    log_tx: &Sender<AppMessage>,
+    log_tx: &UiSender<AppMessage>,
 ) -> bool {
     log_msg(log_tx, "\n=== Checking Rust Toolchain ===\n");
 
@@ -304,10 +303,25 @@ async fn check_rust_installation(
     }
 }
 
+// ─── Package report ───────────────────────────────────────────────────────────
+
+/// `brew list --versions` for every required package, in catalog order.
+/// Packages that are not installed map to `None`.
+pub async fn package_versions(
+    brew: &str,
+    env: &HashMap<String, String>,
+) -> Vec<(&'static str, Option<String>)> {
+    let mut versions = Vec::with_capacity(BREW_PACKAGES.len());
+    for &pkg in BREW_PACKAGES {
+        versions.push((pkg, probe(&[brew, "list", "--versions", pkg], env).await));
+    }
+    versions
+}
+
 // ─── Confirmation helper ──────────────────────────────────────────────────────
 
 /// Send a `ConfirmRequest` to the UI, then suspend until the UI replies.
-async fn ask_confirm(tx: &Sender<ConfirmRequest>, title: &str, message: &str) -> bool {
+async fn ask_confirm(tx: &UiSender<ConfirmRequest>, title: &str, message: &str) -> bool {
     let (response_tx, response_rx) = oneshot::channel::<bool>();
     tx.send(ConfirmRequest {
         title: title.to_owned(),
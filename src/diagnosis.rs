@@ -0,0 +1,245 @@
+// src/diagnosis.rs
+//
+// Failure diagnosis: match the output of a failed build step against a
+// catalog of known failure signatures and turn it into a cause + fix.
+//
+// The catalog is ordered most-specific first — an out-of-memory kill also
+// prints generic "error:" lines, and a missing libevent is also a missing
+// pkg-config module — and `diagnose` reports at most `MAX_DIAGNOSES` hits.
+
+use crate::process::CommandFailed;
+
+const MAX_DIAGNOSES: usize = 3;
+
+/// Id of the out-of-memory signature; a build failing with it can be
+/// retried at lower parallelism.
+pub const OOM_KILL: &str = "oom-kill";
+
+// ─── Catalog ──────────────────────────────────────────────────────────────────
+
+pub struct Signature {
+    /// Stable identifier, e.g. for history or diagnostics bundles.
+    pub id: &'static str,
+    pub title: &'static str,
+    /// Case-insensitive substrings; any one matching line triggers the entry.
+    patterns: &'static [&'static str],
+    pub cause: &'static str,
+    pub fix: &'static str,
+}
+
+static CATALOG: &[Signature] = &[
+    Signature {
+        id: OOM_KILL,
+        title: "Compiler killed — out of memory",
+        patterns: &[
+            "killed: 9",
+            "signal: 9, sigkill",
+            "unable to execute command: killed",
+            "killed signal terminated program",
+            "llvm error: out of memory",
+            "virtual memory exhausted",
+            "std::bad_alloc",
+            "memory allocation of",
+        ],
+        cause: "The system ran out of memory and the kernel killed a compiler process. \
+                Bitcoin Core's largest translation units need 1–2 GB each, so a high \
+                job count multiplies peak memory.",
+        fix: "Lower \"CPU Cores\" (try half the current value), close memory-heavy apps, \
+              then start the build again.",
+    },
+    Signature {
+        id: "disk-full",
+        title: "Disk full",
+        patterns: &[
+            "no space left on device",
+            "enospc",
+            "disk full",
+            "out of disk space",
+        ],
+        cause: "The volume holding the build directory ran out of free space.",
+        fix: "Free at least 10 GB on that volume (old `bitcoin-*` / `electrs-*` source trees \
+              and `target/` dirs in the build directory are safe to delete), or choose an \
+              output directory on a larger disk.",
+    },
+    Signature {
+        id: "libclang-missing",
+        title: "libclang not found (RocksDB bindgen)",
+        patterns: &[
+            "unable to find libclang",
+            "couldn't find any valid shared libraries matching: ['libclang",
+            "the `libclang_path` environment variable",
+            "libclang.dylib",
+        ],
+        cause: "Electrs' RocksDB bindings are generated with bindgen, which needs \
+                libclang from LLVM at build time.",
+        fix: "Run `brew install llvm`, then restart BitForge so LIBCLANG_PATH is set to \
+              `$(brew --prefix llvm)/lib`.",
+    },
+    Signature {
+        id: "xcode-clt-missing",
+        title: "Xcode Command Line Tools missing",
+        patterns: &[
+            "xcrun: error: invalid active developer path",
+            "no cmake_c_compiler could be found",
+            "no cmake_cxx_compiler could be found",
+            "xcode-select: note: no developer tools were found",
+        ],
+        cause: "No working C/C++ compiler was found; macOS ships clang only with the \
+                Xcode Command Line Tools.",
+        fix: "Run `xcode-select --install`, finish the installer, then build again.",
+    },
+    Signature {
+        id: "libevent-missing",
+        title: "libevent not found",
+        patterns: &[
+            "could not find libevent",
+            "no package 'libevent' found",
+            "package 'libevent', required by",
+        ],
+        cause: "Bitcoin Core's only required third-party dependency, libevent, is not \
+                installed or not visible to pkg-config.",
+        fix: "Run `brew install libevent pkg-config`, then build again.",
+    },
+    Signature {
+        id: "pkg-config-module-missing",
+        title: "pkg-config module not found",
+        patterns: &[
+            "no package '",
+            "a required package was not found",
+            "could not find pkgconfig",
+            "pkg-config: command not found",
+            "none of the required",
+        ],
+        cause: "A dependency that cmake or a build script looks up through pkg-config is \
+                not installed, or its `.pc` file is outside PKG_CONFIG_PATH.",
+        fix: "Install the package named in the log with Homebrew (and `brew install \
+              pkg-config` if pkg-config itself is missing). Homebrew's pkgconfig \
+              directories are already on PKG_CONFIG_PATH.",
+    },
+    Signature {
+        id: "boost-missing",
+        title: "Boost not found",
+        patterns: &["could not find boost", "fatal error: 'boost/"],
+        cause: "Boost headers are required by this Bitcoin Core version but were not found.",
+        fix: "Run `brew install boost`, then build again.",
+    },
+    Signature {
+        id: "compiler-too-old",
+        title: "Unsupported compiler or toolchain version",
+        patterns: &[
+            "requires rustc",
+            "is not supported by this version",
+            "c++20 support is required",
+            "does not support c++20",
+            "unsupported compiler",
+            "compiler version too old",
+            "or higher is required. you are running version",
+            "feature `edition2024` is required",
+        ],
+        cause: "This release needs a newer compiler, Rust toolchain or CMake than the one \
+                on PATH.",
+        fix: "Update the toolchain: `softwareupdate --install -a` (or reinstall the Xcode \
+              Command Line Tools) for clang, `brew upgrade cmake rust` (or `rustup \
+              update`) for CMake and Rust. Then build again.",
+    },
+    Signature {
+        id: "tool-missing",
+        title: "Build tool not on PATH",
+        patterns: &["command not found", "no such file or directory: 'cmake'"],
+        cause: "A required command-line tool (cmake, cargo, git, …) is not installed or \
+                not on PATH.",
+        fix: "Click \"Check & Install\" in Step 1 to install missing tools, then restart \
+              BitForge.",
+    },
+    Signature {
+        id: "tag-not-found",
+        title: "Version tag not found",
+        patterns: &[
+            "not found in upstream origin",
+            "could not find remote branch",
+        ],
+        cause: "The selected version tag does not exist in the upstream repository.",
+        fix: "Click \"Refresh\" next to the version list and pick a listed release.",
+    },
+    Signature {
+        id: "network",
+        title: "Network error",
+        patterns: &[
+            "could not resolve host",
+            "unable to access 'https://",
+            "failed to download",
+            "spurious network error",
+            "connection timed out",
+            "failed to connect to",
+        ],
+        cause: "A download (git clone or cargo fetching crates) failed.",
+        fix: "Check your internet connection / proxy settings and build again; partial \
+              downloads are resumed.",
+    },
+    Signature {
+        id: "permission-denied",
+        title: "Permission denied",
+        patterns: &[
+            "permission denied",
+            "operation not permitted",
+            "read-only file system",
+        ],
+        cause: "BitForge could not write to the build directory or one of its files.",
+        fix: "Choose an output directory you own (e.g. under ~/Downloads), or fix the \
+              directory's permissions.",
+    },
+];
+
+// ─── Matching ─────────────────────────────────────────────────────────────────
+
+/// A catalog entry that matched, with the first line that triggered it.
+pub struct Diagnosis {
+    pub signature: &'static Signature,
+    pub evidence: String,
+}
+
+/// Diagnose a failed build step from its error chain and, when the failure
+/// came from `run_command`, the captured tail of the command's output.
+pub fn diagnose(err: &anyhow::Error) -> Vec<Diagnosis> {
+    let mut text: String = err.chain().map(|c| format!("{c}\n")).collect();
+    if let Some(failed) = err.chain().find_map(|c| c.downcast_ref::<CommandFailed>()) {
+        text.push_str(&failed.output);
+    }
+    diagnose_output(&text)
+}
+
+/// Match `output` against the catalog, most specific signature first.
+pub fn diagnose_output(output: &str) -> Vec<Diagnosis> {
+    let lines: Vec<(String, &str)> = output
+        .lines()
+        .map(|l| (crate::ansi::strip(l).to_ascii_lowercase(), l))
+        .collect();
+
+    CATALOG
+        .iter()
+        .filter_map(|sig| {
+            lines
+                .iter()
+                .find(|(lower, _)| sig.patterns.iter().any(|p| lower.contains(p)))
+                .map(|(_, line)| Diagnosis {
+                    signature: sig,
+                    evidence: crate::ansi::strip(line.trim()),
+                })
+        })
+        .take(MAX_DIAGNOSES)
+        .collect()
+}
+
+/// Human-readable report for the failure dialog and the log.
+pub fn format_report(diagnoses: &[Diagnosis]) -> String {
+    diagnoses
+        .iter()
+        .map(|d| {
+            format!(
+                "🔎 {}\n{}\n👉 Fix: {}\n   Matched: {}\n",
+                d.signature.title, d.signature.cause, d.signature.fix, d.evidence
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -3,6 +3,25 @@
 // Homebrew discovery and build environment construction.
 
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+// ─── User directories ─────────────────────────────────────────────────────────
+
+/// The current user's home directory, from `$HOME`.
+#[must_use]
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// Per-user BitForge data directory (`~/Library/Application Support/BitForge`).
+/// Falls back to `/tmp/BitForge` when `$HOME` is unset.
+#[must_use]
+pub fn app_data_dir() -> PathBuf {
+    home_dir().map_or_else(
+        || PathBuf::from("/tmp/BitForge"),
+        |h| h.join("Library/Application Support/BitForge"),
+    )
+}
 
 // ─── Homebrew discovery ───────────────────────────────────────────────────────
 
@@ -0,0 +1,170 @@
+// src/history.rs
+//
+// Persistent build history.
+//
+// Every compile run appends one `BuildRecord` per project (Bitcoin Core or
+// Electrs) to a JSON-lines file in the BitForge data directory.  JSON lines
+// keeps writes append-only — a crash mid-write can only ever damage the last
+// record, and `load` simply skips lines it cannot parse.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::env_setup::app_data_dir;
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+// ─── Record types ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuildResult {
+    Succeeded,
+    Failed,
+}
+
+impl BuildResult {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// One finished build of a single project.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BuildRecord {
+    /// `"Bitcoin"` or `"Electrs"` — never `"Both"`; a combined run produces
+    /// one record per project.
+    pub target: String,
+    pub version: String,
+    /// Configure / build flags passed to cmake or cargo.
+    pub flags: Vec<String>,
+    pub cores: usize,
+    /// Unix timestamps (seconds).
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub result: BuildResult,
+    pub error: Option<String>,
+    /// Ids of the failure signatures `diagnosis::diagnose` matched.
+    #[serde(default)]
+    pub diagnoses: Vec<String>,
+    pub build_dir: PathBuf,
+    pub output_dir: Option<PathBuf>,
+    pub log_file: Option<PathBuf>,
+}
+
+impl BuildRecord {
+    pub const fn duration_secs(&self) -> u64 {
+        self.finished_at.saturating_sub(self.started_at)
+    }
+}
+
+// ─── Persistence ──────────────────────────────────────────────────────────────
+
+fn history_path() -> PathBuf {
+    app_data_dir().join(HISTORY_FILE)
+}
+
+/// Load every readable record, oldest first.
+/// A missing file yields an empty history; corrupt lines are skipped.
+pub fn load() -> Vec<BuildRecord> {
+    let Ok(text) = std::fs::read_to_string(history_path()) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// Append `record` to the history file, creating the data directory if needed.
+pub async fn append(record: &BuildRecord) -> Result<()> {
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    let mut line = serde_json::to_string(record).context("Failed to serialise build record")?;
+    line.push('\n');
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    file.flush().await.ok();
+    Ok(())
+}
+
+// ─── Time helpers ─────────────────────────────────────────────────────────────
+
+/// Current time as Unix seconds.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Format Unix seconds as `YYYY-MM-DD HH:MM:SS` (UTC).
+pub fn format_utc(secs: u64) -> String {
+    let (y, mo, d, h, mi, s) = civil_from_unix(secs);
+    format!("{y:04}-{mo:02}-{d:02} {h:02}:{mi:02}:{s:02}")
+}
+
+/// Format the time of day of a `SystemTime` as `HH:MM:SS` (UTC).
+pub fn format_utc_time(t: SystemTime) -> String {
+    let secs = t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (_, _, _, h, mi, s) = civil_from_unix(secs);
+    format!("{h:02}:{mi:02}:{s:02}")
+}
+
+/// Format Unix seconds as `YYYYMMDD-HHMMSS` (UTC), safe for file names.
+pub fn format_utc_compact(secs: u64) -> String {
+    let (y, mo, d, h, mi, s) = civil_from_unix(secs);
+    format!("{y:04}{mo:02}{d:02}-{h:02}{mi:02}{s:02}")
+}
+
+/// Format a duration as `1h 02m 03s` / `2m 03s` / `3s`.
+pub fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m:02}m {s:02}s")
+    } else if m > 0 {
+        format!("{m}m {s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+/// Split Unix seconds into a UTC `(year, month, day, hour, minute, second)`.
+///
+/// Uses Howard Hinnant's `civil_from_days` algorithm so we don't need a
+/// date/time dependency for a handful of timestamps.
+pub const fn civil_from_unix(secs: u64) -> (u64, u64, u64, u64, u64, u64) {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of a year.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day, rem / 3600, (rem / 60) % 60, rem % 60)
+}
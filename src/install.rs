@@ -0,0 +1,266 @@
+// src/install.rs
+//
+// Install built binaries onto the PATH, with per-project version switching.
+//
+// For a chosen bin directory (e.g. `~/.local/bin`) the layout is:
+//
+//   <bin>/../libexec/bitforge/bitcoin/29.0/bitcoind      installed copies
+//   <bin>/../libexec/bitforge/bitcoin/28.1/bitcoind
+//   <bin>/../libexec/bitforge/bitcoin/current -> 29.0    active version
+//   <bin>/bitcoind -> ../libexec/bitforge/bitcoin/current/bitcoind
+//
+// The bin links resolve through `current`, so switching or rolling back is a
+// single atomic `rename` of a fresh symlink over `current` — a shell never
+// sees half of one version and half of another.  Versions are copied into a
+// staging directory and renamed into place for the same reason.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+
+const CURRENT: &str = "current";
+
+/// One project (`bitcoin` / `electrs`) installed under a bin directory.
+#[derive(Clone, Debug)]
+pub struct InstalledProject {
+    pub project: String,
+    /// Installed versions, sorted.
+    pub versions: Vec<String>,
+    /// Version `current` points at.
+    pub current: Option<String>,
+}
+
+/// `<bin>/../libexec/bitforge`.
+pub fn install_root(bin_dir: &Path) -> PathBuf {
+    bin_dir
+        .parent()
+        .unwrap_or(bin_dir)
+        .join("libexec")
+        .join("bitforge")
+}
+
+/// Split a `binaries/` folder name such as `bitcoin-29.0` into
+/// `("bitcoin", "29.0")`.
+pub fn parse_build_name(name: &str) -> Option<(&str, &str)> {
+    name.split_once('-')
+        .filter(|(project, version)| !project.is_empty() && !version.is_empty())
+}
+
+/// Whether `dir` is one of the directories on this process's `PATH`.
+pub fn on_path(dir: &Path) -> bool {
+    std::env::var_os("PATH").is_some_and(|p| std::env::split_paths(&p).any(|d| d == dir))
+}
+
+// ─── Listing ──────────────────────────────────────────────────────────────────
+
+/// Everything installed under `bin_dir`.  Blocking but cheap (no tree walk).
+pub fn installed(bin_dir: &Path) -> Vec<InstalledProject> {
+    let root = install_root(bin_dir);
+    let Ok(rd) = std::fs::read_dir(&root) else {
+        return Vec::new();
+    };
+    let mut projects: Vec<InstalledProject> = rd
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| {
+            let dir = e.path();
+            let mut versions: Vec<String> = std::fs::read_dir(&dir)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .filter(|v| v.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|v| v.file_name().to_string_lossy().into_owned())
+                .filter(|v| !v.starts_with('.'))
+                .collect();
+            versions.sort();
+            let current = std::fs::read_link(dir.join(CURRENT))
+                .ok()
+                .map(|t| t.to_string_lossy().into_owned());
+            InstalledProject {
+                project: e.file_name().to_string_lossy().into_owned(),
+                versions,
+                current,
+            }
+        })
+        .collect();
+    projects.sort_by(|a, b| a.project.cmp(&b.project));
+    projects
+}
+
+// ─── Install / activate ───────────────────────────────────────────────────────
+
+/// Copy the executables in `binaries_dir` (a `binaries/<project>-<version>`
+/// folder) into the install root and make that version current.  Other
+/// files there, such as the option catalog, are not installed.
+pub async fn install(binaries_dir: &Path, bin_dir: &Path, tx: &UiSender<AppMessage>) -> Result<()> {
+    let folder = binaries_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (project, version) = parse_build_name(&folder)
+        .with_context(|| format!("Not a <project>-<version> folder: {folder}"))?;
+
+    let project_dir = install_root(bin_dir).join(project);
+    let version_dir = project_dir.join(version);
+    let staging = project_dir.join(format!(".{version}.partial"));
+
+    log_msg(
+        tx,
+        &format!(
+            "\n📦 Installing {project} {version} into {}...\n",
+            bin_dir.display()
+        ),
+    );
+
+    if staging.exists() {
+        tokio::fs::remove_dir_all(&staging).await.ok();
+    }
+    tokio::fs::create_dir_all(&staging)
+        .await
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    let mut rd = tokio::fs::read_dir(binaries_dir)
+        .await
+        .with_context(|| format!("Failed to read {}", binaries_dir.display()))?;
+    let mut copied = 0;
+    while let Some(entry) = rd.next_entry().await? {
+        let src = entry.path();
+        if !is_executable(&src) {
+            continue;
+        }
+        let dest = staging.join(entry.file_name());
+        tokio::fs::copy(&src, &dest)
+            .await
+            .with_context(|| format!("Failed to copy {}", src.display()))?;
+        copied += 1;
+    }
+    if copied == 0 {
+        tokio::fs::remove_dir_all(&staging).await.ok();
+        anyhow::bail!("No binaries found in {}", binaries_dir.display());
+    }
+
+    // Reinstalling a version: move the old copy aside first, so a live
+    // `current` link is only without a target between two renames.
+    let old = project_dir.join(format!(".{version}.old"));
+    if version_dir.exists() {
+        tokio::fs::remove_dir_all(&old).await.ok();
+        tokio::fs::rename(&version_dir, &old)
+            .await
+            .with_context(|| format!("Failed to replace {}", version_dir.display()))?;
+    }
+    tokio::fs::rename(&staging, &version_dir)
+        .await
+        .with_context(|| format!("Failed to move install into {}", version_dir.display()))?;
+    tokio::fs::remove_dir_all(&old).await.ok();
+    log_msg(
+        tx,
+        &format!("  ✓ {copied} files in {}\n", version_dir.display()),
+    );
+
+    activate(bin_dir, project, version, tx).await
+}
+
+/// Point `project`'s `current` link at `version` and make sure every one of
+/// its binaries has a link in `bin_dir`.
+pub async fn activate(
+    bin_dir: &Path,
+    project: &str,
+    version: &str,
+    tx: &UiSender<AppMessage>,
+) -> Result<()> {
+    let project_dir = install_root(bin_dir).join(project);
+    let version_dir = project_dir.join(version);
+    if !version_dir.is_dir() {
+        anyhow::bail!("{project} {version} is not installed");
+    }
+
+    // Atomic switch: build the new link beside `current`, then rename over it.
+    let current = project_dir.join(CURRENT);
+    let tmp = project_dir.join(format!(".{CURRENT}.{}", std::process::id()));
+    tokio::fs::remove_file(&tmp).await.ok();
+    tokio::fs::symlink(version, &tmp)
+        .await
+        .with_context(|| format!("Failed to create {}", tmp.display()))?;
+    tokio::fs::rename(&tmp, &current)
+        .await
+        .with_context(|| format!("Failed to update {}", current.display()))?;
+
+    tokio::fs::create_dir_all(bin_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", bin_dir.display()))?;
+    let link_base = Path::new("..")
+        .join("libexec")
+        .join("bitforge")
+        .join(project)
+        .join(CURRENT);
+
+    let mut rd = tokio::fs::read_dir(&version_dir).await?;
+    while let Some(entry) = rd.next_entry().await? {
+        let name = entry.file_name();
+        let link = bin_dir.join(&name);
+        let target = link_base.join(&name);
+        match tokio::fs::symlink_metadata(&link).await {
+            Err(_) => {
+                tokio::fs::symlink(&target, &link)
+                    .await
+                    .with_context(|| format!("Failed to link {}", link.display()))?;
+            }
+            Ok(_) if tokio::fs::read_link(&link).await.ok().as_deref() == Some(&target) => {}
+            Ok(_) => log_msg(
+                tx,
+                &format!(
+                    "  ⚠  {} already exists and is not ours — left untouched\n",
+                    link.display()
+                ),
+            ),
+        }
+    }
+
+    remove_dangling_links(bin_dir, &link_base).await;
+    log_msg(
+        tx,
+        &format!(
+            "✓ {project} {version} is now active in {}\n",
+            bin_dir.display()
+        ),
+    );
+    if !on_path(bin_dir) {
+        log_msg(
+            tx,
+            &format!(
+                "  ⚠  {} is not on your PATH — add it to your shell profile.\n",
+                bin_dir.display()
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// Remove our links in `bin_dir` for binaries the active version lacks
+/// (e.g. `bitcoin-tx`, dropped in Bitcoin Core v29).
+async fn remove_dangling_links(bin_dir: &Path, link_base: &Path) {
+    let Ok(mut rd) = tokio::fs::read_dir(bin_dir).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = rd.next_entry().await {
+        let link = entry.path();
+        let ours = tokio::fs::read_link(&link)
+            .await
+            .is_ok_and(|t| t.starts_with(link_base));
+        if ours && tokio::fs::metadata(&link).await.is_err() {
+            tokio::fs::remove_file(&link).await.ok();
+        }
+    }
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    path.is_file()
+}
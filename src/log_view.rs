@@ -0,0 +1,305 @@
+// src/log_view.rs
+//
+// Line storage, classification, filtering and search for the build log panel.
+//
+// Classification is deliberately pattern-based rather than tool-aware: a
+// single Bitcoin Core build interleaves cmake, clang and ld output, and an
+// Electrs build interleaves cargo and rustc, so every line is checked
+// against the union of their error / warning markers.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::SystemTime;
+
+use crate::ansi::{self, AnsiStyle};
+use crate::messages::{LogRecord, LogStream};
+
+// ─── Stored lines ─────────────────────────────────────────────────────────────
+
+/// One terminal line plus the origin of the record that started it.
+///
+/// The styled spans, plain text and kind are derived from `text` once, by
+/// `restyle`, whenever the line changes — not on every frame — so rendering
+/// a row and filtering or searching the log never re-parse escapes.
+pub struct LogLine {
+    /// Line text without the trailing newline; may contain ANSI escapes.
+    pub text: String,
+    pub stream: LogStream,
+    pub command_id: Option<u64>,
+    pub timestamp: SystemTime,
+    /// `false` while the line is still being written (no `\n` seen yet).
+    pub complete: bool,
+    /// `text` split into styled runs, escapes removed.
+    pub spans: Vec<(String, AnsiStyle)>,
+    /// `text` with escapes removed (the concatenated `spans`).
+    pub plain: String,
+    pub kind: LineKind,
+    /// SGR state after this line; the next line starts from it.
+    pub end_style: AnsiStyle,
+}
+
+impl LogLine {
+    pub fn open(record: &LogRecord) -> Self {
+        Self {
+            text: String::new(),
+            stream: record.stream,
+            command_id: record.command_id,
+            timestamp: record.timestamp,
+            complete: false,
+            spans: Vec::new(),
+            plain: String::new(),
+            kind: LineKind::Normal,
+            end_style: AnsiStyle::default(),
+        }
+    }
+
+    /// Re-derive the cached spans, plain text and kind from `text`, starting
+    /// from the SGR state `style` left by the previous line.
+    pub fn restyle(&mut self, mut style: AnsiStyle) {
+        self.spans = ansi::parse_line(&self.text, &mut style);
+        self.plain = self.spans.iter().map(|(text, _)| text.as_str()).collect();
+        self.kind = classify_line(&self.plain);
+        self.end_style = style;
+    }
+
+    /// Whether text from `record` continues this line rather than starting a
+    /// new one.  Interleaved stdout / stderr never merge into one line.
+    pub fn continues(&self, record: &LogRecord) -> bool {
+        !self.complete && self.stream == record.stream && self.command_id == record.command_id
+    }
+
+    /// The `$ cmd` header of a `run_command` invocation.
+    pub fn is_command_header(&self) -> bool {
+        self.stream == LogStream::Command
+    }
+
+    /// Whether the line is shown under `filter` with the commands in
+    /// `collapsed` folded.  Command headers always show so a collapsed
+    /// command can be expanded again.
+    pub fn visible(&self, filter: LogFilter, collapsed: &HashSet<u64>) -> bool {
+        if self.is_command_header() {
+            return true;
+        }
+        let folded = self.command_id.is_some_and(|id| collapsed.contains(&id));
+        !folded && filter.accepts(self.kind)
+    }
+}
+
+// ─── Visible rows ─────────────────────────────────────────────────────────────
+
+/// The terminal's rows under the current filter, collapse state and search.
+/// New lines are indexed as they arrive and dropped lines forgotten, so a
+/// chatty build costs work per new line, not per buffered line.  A full
+/// `build` is only needed when the filter, collapse state or search changes.
+/// Rendering then lays out just the rows scrolled into view.
+///
+/// Lines, rows and matches are numbered from the first one ever indexed, so
+/// dropping old lines from the front never renumbers the rest.
+#[derive(Default)]
+pub struct LogIndex {
+    /// Line number of each visible line, in order.
+    rows: VecDeque<usize>,
+    /// For each row, the number of search matches in the rows before it.
+    matches_before: VecDeque<usize>,
+    /// The row number of every search match, in order.
+    match_rows: VecDeque<usize>,
+    /// Lines, rows and matches dropped from the front so far.
+    lines_dropped: usize,
+    rows_dropped: usize,
+    matches_dropped: usize,
+}
+
+impl LogIndex {
+    pub fn build(
+        lines: &VecDeque<LogLine>,
+        filter: LogFilter,
+        collapsed: &HashSet<u64>,
+        search: &str,
+    ) -> Self {
+        let mut index = Self::default();
+        index.push_lines(lines, 0, filter, collapsed, search);
+        index
+    }
+
+    /// Index `lines[from..]`, which must not be indexed yet.
+    pub fn push_lines(
+        &mut self,
+        lines: &VecDeque<LogLine>,
+        from: usize,
+        filter: LogFilter,
+        collapsed: &HashSet<u64>,
+        search: &str,
+    ) {
+        for (i, line) in lines.iter().enumerate().skip(from) {
+            if !line.visible(filter, collapsed) {
+                continue;
+            }
+            let row = self.rows_dropped + self.rows.len();
+            self.rows.push_back(self.lines_dropped + i);
+            self.matches_before
+                .push_back(self.matches_dropped + self.match_rows.len());
+            let count = find_matches(&line.plain, search).len();
+            self.match_rows.extend(std::iter::repeat_n(row, count));
+        }
+    }
+
+    /// Forget `lines[from..]` so they can be indexed again after changing.
+    pub fn truncate_lines(&mut self, from: usize) {
+        let first = self.lines_dropped + from;
+        while self.rows.back().is_some_and(|&line| line >= first) {
+            self.rows.pop_back();
+            if let Some(before) = self.matches_before.pop_back() {
+                self.match_rows.truncate(before - self.matches_dropped);
+            }
+        }
+    }
+
+    /// Forget the first `count` lines, just dropped from the buffer, and
+    /// return how many search matches went with them.
+    pub fn drop_front_lines(&mut self, count: usize) -> usize {
+        let matches_dropped = self.matches_dropped;
+        self.lines_dropped += count;
+        while self
+            .rows
+            .front()
+            .is_some_and(|&line| line < self.lines_dropped)
+        {
+            self.rows.pop_front();
+            self.matches_before.pop_front();
+            self.rows_dropped += 1;
+        }
+        while self
+            .match_rows
+            .front()
+            .is_some_and(|&row| row < self.rows_dropped)
+        {
+            self.match_rows.pop_front();
+            self.matches_dropped += 1;
+        }
+        self.matches_dropped - matches_dropped
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Buffer index of the line shown at `row`.
+    pub fn line(&self, row: usize) -> usize {
+        self.rows[row] - self.lines_dropped
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.match_rows.len()
+    }
+
+    /// The row holding search match `n`.
+    pub fn match_row(&self, n: usize) -> Option<usize> {
+        self.match_rows.get(n).map(|row| row - self.rows_dropped)
+    }
+
+    /// Search matches in the rows before `row`.
+    pub fn matches_before(&self, row: usize) -> usize {
+        self.matches_before
+            .get(row)
+            .map_or(0, |before| before - self.matches_dropped)
+    }
+}
+
+// ─── Line kinds ───────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    Normal,
+    Warning,
+    Error,
+}
+
+/// Case-insensitive markers for a failing line.
+///   clang / gcc / rustc: `error:`, `fatal error:`, `error[E0433]`
+///   ld (Apple + GNU):    `ld: error`, `undefined symbols for architecture`,
+///                        `undefined reference to`, `symbol(s) not found`
+///   cmake:               `CMake Error`
+///   make / ninja:        `make: ***`, `make[2]: ***`, `FAILED:`
+const ERROR_MARKERS: &[&str] = &[
+    "error:",
+    "error[",
+    "cmake error",
+    "undefined symbols for architecture",
+    "undefined reference to",
+    "symbol(s) not found",
+    "failed:",
+    "❌",
+];
+
+/// Case-insensitive markers for a warning line.
+///   clang / gcc / rustc / cargo / ld: `warning:`, `warning[...]`
+///   cmake:                            `CMake Warning`, `CMake Deprecation Warning`
+const WARNING_MARKERS: &[&str] = &[
+    "warning:",
+    "warning[",
+    "cmake warning",
+    "cmake deprecation warning",
+    "⚠",
+];
+
+/// Classify one log line by the error / warning markers it contains.
+/// Errors win when a line matches both (e.g. `warning: ... treated as error:`).
+pub fn classify_line(line: &str) -> LineKind {
+    let lower = line.to_ascii_lowercase();
+    if ERROR_MARKERS.iter().any(|m| lower.contains(m)) || is_make_failure(&lower) {
+        LineKind::Error
+    } else if WARNING_MARKERS.iter().any(|m| lower.contains(m)) {
+        LineKind::Warning
+    } else {
+        LineKind::Normal
+    }
+}
+
+/// `make: *** [target] Error 2` / `gmake[2]: *** ...`
+fn is_make_failure(lower: &str) -> bool {
+    lower.contains("make") && lower.contains(": ***")
+}
+
+// ─── Filter ───────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFilter {
+    All,
+    Problems,
+    ErrorsOnly,
+}
+
+impl LogFilter {
+    pub const ALL: [Self; 3] = [Self::All, Self::Problems, Self::ErrorsOnly];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::All => "All lines",
+            Self::Problems => "Errors & warnings",
+            Self::ErrorsOnly => "Errors only",
+        }
+    }
+
+    pub const fn accepts(self, kind: LineKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Problems => !matches!(kind, LineKind::Normal),
+            Self::ErrorsOnly => matches!(kind, LineKind::Error),
+        }
+    }
+}
+
+// ─── Search ───────────────────────────────────────────────────────────────────
+
+/// Byte ranges of every ASCII-case-insensitive occurrence of `needle` in
+/// `haystack`.  ASCII lowering keeps byte offsets identical to the original,
+/// so the ranges index straight back into `haystack`.
+pub fn find_matches(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let hay = haystack.to_ascii_lowercase();
+    let needle = needle.to_ascii_lowercase();
+    hay.match_indices(&needle)
+        .map(|(start, m)| (start, start + m.len()))
+        .collect()
+}
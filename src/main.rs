@@ -1,12 +1,29 @@
 // src/main.rs — BitForge entry point.
 
+mod ansi;
 mod app;
 mod compiler;
+mod config_gen;
 mod deps;
+mod diagnosis;
 mod env_setup;
 mod github;
+mod history;
+mod install;
+mod log_view;
 mod messages;
+mod monitor;
+mod nodes;
+mod options;
+mod package;
+mod preflight;
 mod process;
+mod regtest;
+mod signing;
+mod storage;
+mod support_bundle;
+mod systemd;
+mod verify;
 
 use std::sync::Arc;
 
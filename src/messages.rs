@@ -6,16 +6,69 @@
 //
 // Also provides `log_msg`, the single shared helper used by every module
 // to push a line into the UI terminal, eliminating the per-module duplicate.
+//
+// Background tasks send through `UiSender`, which wakes the egui event loop
+// on every send: the UI repaints when there is something new instead of
+// polling the inbox on a timer.
 
-use std::sync::mpsc::Sender;
+use std::path::PathBuf;
+use std::sync::mpsc::{SendError, Sender};
+use std::time::{Duration, SystemTime};
 use tokio::sync::oneshot;
 
+use crate::config_gen::Capabilities;
+use crate::history::BuildRecord;
+use crate::monitor::SystemStats;
+use crate::nodes::{NodeKind, NodeRecord};
+use crate::storage::StorageEntry;
+
+// ─── LogRecord ────────────────────────────────────────────────────────────────
+
+/// Where a piece of log text came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogStream {
+    /// BitForge's own status messages.
+    App,
+    /// The `$ cmd` header `run_command` prints before spawning a command.
+    Command,
+    Stdout,
+    Stderr,
+}
+
+/// One chunk of log text plus its origin.  `text` may hold several lines,
+/// a partial line, or bare `\r` progress updates.
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    /// Id of the `run_command` invocation that produced this text; `None`
+    /// for app messages outside any command.
+    pub command_id: Option<u64>,
+    pub stream: LogStream,
+    pub timestamp: SystemTime,
+    pub text: String,
+}
+
+impl LogRecord {
+    pub fn new(command_id: Option<u64>, stream: LogStream, text: String) -> Self {
+        Self {
+            command_id,
+            stream,
+            timestamp: SystemTime::now(),
+            text,
+        }
+    }
+
+    /// An app-level status message.
+    pub fn app(text: String) -> Self {
+        Self::new(None, LogStream::App, text)
+    }
+}
+
 // ─── AppMessage ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
 pub enum AppMessage {
     /// Append text to the dark terminal log widget.
-    Log(String),
+    Log(LogRecord),
 
     /// Set the progress bar value (0.0 – 1.0).
     Progress(f32),
@@ -33,10 +86,49 @@ pub enum AppMessage {
         is_error: bool,
     },
 
+    /// Tee every following `Log` message, untrimmed, into this file.
+    LogFileStarted(PathBuf),
+
+    /// Close the file opened by `LogFileStarted`.
+    LogFileFinished,
+
+    /// A project finished building (either way) and was saved to history.
+    BuildRecorded(BuildRecord),
+
+    /// Latest CPU / memory / disk reading while a build runs.
+    SystemStats(SystemStats),
+
+    /// Result of scanning the build directory for the Storage window.
+    StorageScanned(Vec<StorageEntry>),
+
+    /// What the selected builds support, for the Configuration window.
+    ConfigCapabilities(Capabilities),
+
+    /// A node was started from the Nodes window.
+    NodeStarted(NodeRecord),
+    /// Starting a node failed; the error follows as a dialog.
+    NodeStartFailed(NodeKind),
+    /// New output from a running node's log.
+    NodeOutput { kind: NodeKind, text: String },
+    /// A tracked node's process has exited.
+    NodeExited(NodeKind),
+
+    /// An install or version switch finished; refresh the Install window.
+    InstallChanged,
+
     /// A background task completed — re-enable the Compile button.
     TaskDone,
 }
 
+impl UiMessage for AppMessage {
+    fn repaint_delay(&self) -> Duration {
+        match self {
+            Self::Log(_) | Self::NodeOutput { .. } => LOG_REPAINT_DELAY,
+            _ => Duration::ZERO,
+        }
+    }
+}
+
 // ─── ConfirmRequest ───────────────────────────────────────────────────────────
 
 pub struct ConfirmRequest {
@@ -46,11 +138,64 @@ pub struct ConfirmRequest {
     pub response_tx: oneshot::Sender<bool>,
 }
 
+impl UiMessage for ConfirmRequest {}
+
+// ─── UiSender ─────────────────────────────────────────────────────────────────
+
+/// Log output arrives in bursts of many small messages; waking the UI at most
+/// this long after the first one folds a burst into a single repaint.
+const LOG_REPAINT_DELAY: Duration = Duration::from_millis(16);
+
+/// A message the UI thread consumes.
+pub trait UiMessage {
+    /// How soon after this message is sent the UI must repaint.  Repaint
+    /// requests coalesce, so everything sent before then shares one frame.
+    fn repaint_delay(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// `mpsc::Sender` that also wakes the egui event loop, so the UI thread
+/// drains its inbox as soon as something arrives.
+pub struct UiSender<T> {
+    tx: Sender<T>,
+    ctx: egui::Context,
+}
+
+// Derived `Clone` would require `T: Clone`.
+impl<T> Clone for UiSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            ctx: self.ctx.clone(),
+        }
+    }
+}
+
+impl<T: UiMessage> UiSender<T> {
+    pub const fn new(tx: Sender<T>, ctx: egui::Context) -> Self {
+        Self { tx, ctx }
+    }
+
+    /// Send `msg` and schedule a repaint.  Fails only once the UI is gone.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let delay = msg.repaint_delay();
+        self.tx.send(msg)?;
+        if delay.is_zero() {
+            self.ctx.request_repaint();
+        } else {
+            self.ctx.request_repaint_after(delay);
+        }
+        Ok(())
+    }
+}
+
 // ─── Shared log helper ────────────────────────────────────────────────────────
 
 /// Push a log line to the UI terminal.
 /// Errors are silently ignored — the UI may be shutting down.
 #[inline]
-pub fn log_msg(tx: &Sender<AppMessage>, msg: &str) {
-    tx.send(AppMessage::Log(msg.to_owned())).ok();
+pub fn log_msg(tx: &UiSender<AppMessage>, msg: &str) {
+    tx.send(AppMessage::Log(LogRecord::app(msg.to_owned())))
+        .ok();
 }
@@ -0,0 +1,210 @@
+// src/monitor.rs
+//
+// Live system monitor shown in the status bar while a build runs.
+//
+// Bitcoin Core's heaviest translation units need 1–2 GB each, so a high `-j`
+// can push the machine into swap long before anything fails.  `run` samples
+// CPU load, memory, swap and the build directory's free space every few
+// seconds and logs a warning when available memory drops below
+// `PRESSURE_PERCENT` of RAM — early enough to lower "CPU Cores" and restart.
+//
+// `recommend_jobs` works the same problem from the other end: it picks a job
+// count that fits the memory available before the build starts.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sysinfo::{Disks, System};
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Available memory below this share of RAM counts as memory pressure.
+const PRESSURE_PERCENT: u64 = 10;
+/// Pressure ends once available memory is back above this share, so a
+/// value hovering around the threshold does not re-warn every sample.
+const RELIEF_PERCENT: u64 = 20;
+
+/// One reading of system load.  Sizes are bytes.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemStats {
+    /// Average over all cores, 0–100.
+    pub cpu_percent: f32,
+    pub memory_total: u64,
+    pub memory_available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    /// Free space on the volume holding the build directory.
+    pub build_dir_free: Option<u64>,
+}
+
+impl SystemStats {
+    pub const fn memory_available_percent(&self) -> u64 {
+        if self.memory_total == 0 {
+            return 100;
+        }
+        self.memory_available * 100 / self.memory_total
+    }
+
+    pub const fn under_pressure(&self) -> bool {
+        self.memory_available_percent() < PRESSURE_PERCENT
+    }
+
+    /// `CPU 87% · Mem 1.2 GB free of 8.0 GB · Swap 0.4 / 2.0 GB · Disk 41.3 GB free`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            format!("CPU {:.0}%", self.cpu_percent),
+            format!(
+                "Mem {} free of {}",
+                format_bytes(self.memory_available),
+                format_bytes(self.memory_total)
+            ),
+        ];
+        if self.swap_total > 0 {
+            parts.push(format!(
+                "Swap {} / {}",
+                format_bytes(self.swap_used),
+                format_bytes(self.swap_total)
+            ));
+        }
+        if let Some(free) = self.build_dir_free {
+            parts.push(format!("Disk {} free", format_bytes(free)));
+        }
+        parts.join("  ·  ")
+    }
+}
+
+// ─── Job count recommendation ─────────────────────────────────────────────────
+
+/// Peak memory of one parallel job: a clang process on Bitcoin Core's largest
+/// C++ translation units, or a rustc process (and RocksDB's C++ via `cc`)
+/// for Electrs.
+const CPP_JOB_MEMORY: u64 = 1_500_000_000;
+const RUST_JOB_MEMORY: u64 = 1_000_000_000;
+/// Memory left for macOS, BitForge and whatever else is running.
+const RESERVED_MEMORY: u64 = 1_500_000_000;
+
+/// A job count that fits both the CPU and the memory available right now.
+#[derive(Clone, Copy, Debug)]
+pub struct JobAdvice {
+    pub jobs: usize,
+    pub memory_available: u64,
+    /// Estimated peak memory per job for the selected target.
+    pub per_job: u64,
+    /// Whether memory, rather than the core count, set the limit.
+    pub memory_bound: bool,
+}
+
+/// Recommend a job count for building `target` (`"Bitcoin"`, `"Electrs"` or
+/// `"Both"`, which is sized for the C++ build) on `max_cores` cores: one
+/// core is left for the UI, and every job must fit in available memory.
+pub fn recommend_jobs(target: &str, max_cores: usize) -> JobAdvice {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    let memory_available = sys.available_memory();
+
+    let per_job = if target == "Electrs" {
+        RUST_JOB_MEMORY
+    } else {
+        CPP_JOB_MEMORY
+    };
+    let by_cores = max_cores.saturating_sub(1).max(1);
+    let by_memory = usize::try_from(memory_available.saturating_sub(RESERVED_MEMORY) / per_job)
+        .unwrap_or(usize::MAX)
+        .max(1);
+
+    JobAdvice {
+        jobs: by_cores.min(by_memory),
+        memory_available,
+        per_job,
+        memory_bound: by_memory < by_cores,
+    }
+}
+
+// ─── Sampling ─────────────────────────────────────────────────────────────────
+
+/// Sample system load until the task is aborted, sending each reading as
+/// `AppMessage::SystemStats` and logging memory-pressure warnings.
+pub async fn run(build_dir: PathBuf, tx: UiSender<AppMessage>) {
+    let mut sys = System::new();
+    let mut under_pressure = false;
+    // The first CPU reading is always 0 — usage is a delta between refreshes.
+    sys.refresh_cpu_usage();
+
+    loop {
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        // Listing the mounted disks can block on a slow or network volume,
+        // so sample off the async workers.
+        let dir = build_dir.clone();
+        let sampled = tokio::task::spawn_blocking(move || {
+            let stats = sample(&mut sys, &dir);
+            (sys, stats)
+        })
+        .await;
+        let Ok((sampled_sys, stats)) = sampled else {
+            break;
+        };
+        sys = sampled_sys;
+
+        if !under_pressure && stats.under_pressure() {
+            under_pressure = true;
+            log_msg(
+                &tx,
+                &format!(
+                    "\n⚠️  Memory pressure: only {} of {} RAM available \
+                     (swap in use: {}). Parallel compiler jobs may be killed \
+                     or the build may slow to a crawl — consider lowering \
+                     \"CPU Cores\" and restarting the build.\n",
+                    format_bytes(stats.memory_available),
+                    format_bytes(stats.memory_total),
+                    format_bytes(stats.swap_used),
+                ),
+            );
+        } else if under_pressure && stats.memory_available_percent() >= RELIEF_PERCENT {
+            under_pressure = false;
+        }
+
+        if tx.send(AppMessage::SystemStats(stats)).is_err() {
+            break;
+        }
+    }
+}
+
+fn sample(sys: &mut System, build_dir: &Path) -> SystemStats {
+    sys.refresh_cpu_usage();
+    sys.refresh_memory();
+    SystemStats {
+        cpu_percent: sys.global_cpu_usage(),
+        memory_total: sys.total_memory(),
+        memory_available: sys.available_memory(),
+        swap_total: sys.total_swap(),
+        swap_used: sys.used_swap(),
+        build_dir_free: free_space(build_dir),
+    }
+}
+
+/// Free space on the mounted volume that contains `path`: the disk whose
+/// mount point is the longest prefix of it.  Blocking — it lists every
+/// mounted filesystem.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(sysinfo::Disk::available_space)
+}
+
+/// `512 MB` / `1.5 GB` (decimal units, as Finder shows them).
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const GB: f64 = 1e9;
+    const MB: f64 = 1e6;
+    let b = bytes as f64;
+    if b >= GB {
+        format!("{:.1} GB", b / GB)
+    } else {
+        format!("{:.0} MB", b / MB)
+    }
+}
@@ -0,0 +1,342 @@
+// src/nodes.rs
+//
+// A small process manager for running the built `bitcoind` and `electrs`.
+//
+// Nodes are started detached from BitForge — in their own process group, with
+// stdout and stderr redirected to `<app data>/nodes/<binary>.log` — so they
+// keep running when the app quits.  A JSON pid file next to the log records
+// what was started; on the next launch `load_running` picks the node back up
+// if that pid is still the same binary, and `watch` resumes tailing its log.
+//
+// Stopping is graceful: `bitcoin-cli stop` for bitcoind (falling back to
+// SIGTERM), SIGTERM for electrs.  `watch` notices the exit and removes the
+// pid file.
+
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::env_setup::app_data_dir;
+use crate::history::unix_now;
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::process::LineDecoder;
+
+const TAIL_INTERVAL: Duration = Duration::from_millis(250);
+/// How often `watch` checks that the process is still alive (in tail ticks).
+const ALIVE_CHECK_TICKS: u32 = 4;
+/// When re-attaching to a node started earlier, show this much of its log.
+const REATTACH_TAIL_BYTES: u64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NodeKind {
+    Bitcoind,
+    Electrs,
+}
+
+impl NodeKind {
+    pub const ALL: [Self; 2] = [Self::Bitcoind, Self::Electrs];
+
+    pub const fn binary(self) -> &'static str {
+        match self {
+            Self::Bitcoind => "bitcoind",
+            Self::Electrs => "electrs",
+        }
+    }
+
+    /// Prefix of its `binaries/<project>-<version>` folders.
+    pub const fn project(self) -> &'static str {
+        match self {
+            Self::Bitcoind => "bitcoin",
+            Self::Electrs => "electrs",
+        }
+    }
+}
+
+/// A started node, as stored in its pid file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeRecord {
+    pub kind: NodeKind,
+    pub pid: u32,
+    pub version: String,
+    pub binary: PathBuf,
+    pub config: Option<PathBuf>,
+    pub datadir: PathBuf,
+    pub log_file: PathBuf,
+    pub started_at: u64,
+}
+
+/// What to start.
+pub struct NodeLaunch {
+    pub kind: NodeKind,
+    /// `binaries/<project>-<version>`.
+    pub binaries_dir: PathBuf,
+    pub version: String,
+    pub config: Option<PathBuf>,
+    /// bitcoind's `-datadir`, or electrs' `--db-dir`.
+    pub datadir: PathBuf,
+}
+
+fn state_dir() -> PathBuf {
+    app_data_dir().join("nodes")
+}
+
+fn pid_file(kind: NodeKind) -> PathBuf {
+    state_dir().join(format!("{}.pid", kind.binary()))
+}
+
+// ─── Tracking ─────────────────────────────────────────────────────────────────
+
+/// The node of `kind` started by an earlier session (or this one), if it is
+/// still running.  A stale pid file is removed.  Blocking but quick.
+pub fn load_running(kind: NodeKind) -> Option<NodeRecord> {
+    let path = pid_file(kind);
+    let record: NodeRecord = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())?;
+    if is_alive(record.pid, kind.binary()) {
+        Some(record)
+    } else {
+        std::fs::remove_file(&path).ok();
+        None
+    }
+}
+
+/// Whether `pid` is a live (not zombie) process named `name` — a pid file
+/// outliving a reboot may point at an unrelated process by now.
+fn is_alive(pid: u32, name: &str) -> bool {
+    std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "stat=,comm="])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .is_some_and(|o| {
+            let out = String::from_utf8_lossy(&o.stdout);
+            // macOS prints the full executable path, which may contain spaces.
+            let (stat, comm) = out.trim().split_once(' ').unwrap_or_default();
+            let comm = comm.trim();
+            !stat.starts_with('Z') && Path::new(comm).file_name().is_some_and(|n| n == name)
+        })
+}
+
+// ─── Start / stop ─────────────────────────────────────────────────────────────
+
+pub async fn start(launch: &NodeLaunch, env: &HashMap<String, String>) -> Result<NodeRecord> {
+    let kind = launch.kind;
+    if let Some(running) = load_running(kind) {
+        anyhow::bail!("{} is already running (pid {})", kind.binary(), running.pid);
+    }
+    let binary = launch.binaries_dir.join(kind.binary());
+    if !binary.is_file() {
+        anyhow::bail!("{} not found", binary.display());
+    }
+    tokio::fs::create_dir_all(state_dir())
+        .await
+        .context("Failed to create the node state directory")?;
+    tokio::fs::create_dir_all(&launch.datadir)
+        .await
+        .with_context(|| format!("Failed to create {}", launch.datadir.display()))?;
+
+    let log_file = state_dir().join(format!("{}.log", kind.binary()));
+    let log = std::fs::File::create(&log_file)
+        .with_context(|| format!("Failed to create {}", log_file.display()))?;
+    let log_err = log.try_clone()?;
+
+    let mut cmd = tokio::process::Command::new(&binary);
+    match kind {
+        NodeKind::Bitcoind => {
+            cmd.arg(format!("-datadir={}", launch.datadir.display()))
+                .arg("-printtoconsole=1");
+            if let Some(conf) = &launch.config {
+                cmd.arg(format!("-conf={}", conf.display()));
+            }
+        }
+        NodeKind::Electrs => {
+            cmd.arg("--db-dir").arg(&launch.datadir);
+            if let Some(conf) = &launch.config {
+                cmd.arg("--conf").arg(conf);
+            }
+        }
+    }
+    // Own process group: a Ctrl-C aimed at BitForge must not reach the node.
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let child = cmd
+        .env_clear()
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", binary.display()))?;
+    let pid = child
+        .id()
+        .context("The node exited before its pid could be read")?;
+    // Not awaited: the node outlives this session, and tokio reaps it if it
+    // exits while we are still running.
+    drop(child);
+
+    let record = NodeRecord {
+        kind,
+        pid,
+        version: launch.version.clone(),
+        binary,
+        config: launch.config.clone(),
+        datadir: launch.datadir.clone(),
+        log_file,
+        started_at: unix_now(),
+    };
+    let json = serde_json::to_string_pretty(&record).context("Failed to serialise pid file")?;
+    tokio::fs::write(pid_file(kind), json)
+        .await
+        .context("Failed to write the pid file")?;
+    Ok(record)
+}
+
+/// Ask the node to shut down.  `watch` reports when it has exited.
+pub async fn stop(record: &NodeRecord, env: &HashMap<String, String>, tx: &UiSender<AppMessage>) {
+    let name = record.kind.binary();
+    if record.kind == NodeKind::Bitcoind {
+        let cli = record.binary.with_file_name("bitcoin-cli");
+        let mut cmd = tokio::process::Command::new(&cli);
+        cmd.arg(format!("-datadir={}", record.datadir.display()));
+        if let Some(conf) = &record.config {
+            cmd.arg(format!("-conf={}", conf.display()));
+        }
+        let stopped = cmd
+            .arg("stop")
+            .env_clear()
+            .envs(env)
+            .output()
+            .await
+            .is_ok_and(|o| o.status.success());
+        if stopped {
+            log_msg(tx, &format!("⏹ Asked {name} to stop (RPC stop)\n"));
+            return;
+        }
+        log_msg(
+            tx,
+            &format!("  ⚠  RPC stop failed; sending SIGTERM to {name}\n"),
+        );
+    }
+    let sent = tokio::process::Command::new("kill")
+        .args(["-TERM", &record.pid.to_string()])
+        .status()
+        .await
+        .is_ok_and(|s| s.success());
+    if sent {
+        log_msg(
+            tx,
+            &format!("⏹ Sent SIGTERM to {name} (pid {})\n", record.pid),
+        );
+    } else {
+        log_msg(
+            tx,
+            &format!("  ✗ Could not signal {name} (pid {})\n", record.pid),
+        );
+    }
+}
+
+// ─── Tailing ──────────────────────────────────────────────────────────────────
+
+/// Tail the node's log as `AppMessage::NodeOutput` until the process exits,
+/// then remove its pid file and send `AppMessage::NodeExited`.  With
+/// `reattach`, start near the end of an existing log instead of the start.
+pub async fn watch(record: NodeRecord, reattach: bool, tx: UiSender<AppMessage>) {
+    let kind = record.kind;
+    // A reattach offset can land mid-line, even mid-character, so start one
+    // byte early and skip through the first newline: that drops the partial
+    // line, or just the newline ending the line before a whole one.
+    let mut offset = if reattach {
+        tokio::fs::metadata(&record.log_file)
+            .await
+            .map_or(0, |m| m.len().saturating_sub(REATTACH_TAIL_BYTES + 1))
+    } else {
+        0
+    };
+    let mut tail = LogTail {
+        decoder: LineDecoder::default(),
+        skip_partial_line: offset > 0,
+    };
+    let mut tick = 0u32;
+
+    loop {
+        tokio::time::sleep(TAIL_INTERVAL).await;
+        offset = tail.read_new(&record.log_file, offset, kind, &tx).await;
+
+        tick += 1;
+        if tick.is_multiple_of(ALIVE_CHECK_TICKS) {
+            let (pid, name) = (record.pid, kind.binary());
+            let alive = tokio::task::spawn_blocking(move || is_alive(pid, name))
+                .await
+                .unwrap_or(false);
+            if !alive {
+                tail.read_new(&record.log_file, offset, kind, &tx).await;
+                if let Some(text) = tail.decoder.finish() {
+                    tx.send(AppMessage::NodeOutput { kind, text }).ok();
+                }
+                tokio::fs::remove_file(pid_file(kind)).await.ok();
+                tx.send(AppMessage::NodeExited(kind)).ok();
+                return;
+            }
+        }
+    }
+}
+
+/// Decodes a node's log as it grows.  Bytes go through a [`LineDecoder`] so
+/// a multi-byte character split across reads survives.
+struct LogTail {
+    decoder: LineDecoder,
+    /// Drop everything up to the first newline still to come.
+    skip_partial_line: bool,
+}
+
+impl LogTail {
+    /// Send whatever was appended to `path` since `offset`; returns the new
+    /// offset.
+    async fn read_new(
+        &mut self,
+        path: &Path,
+        offset: u64,
+        kind: NodeKind,
+        tx: &UiSender<AppMessage>,
+    ) -> u64 {
+        let Ok(mut file) = tokio::fs::File::open(path).await else {
+            return offset;
+        };
+        let len = file.metadata().await.map_or(0, |m| m.len());
+        // Truncated (restarted with a fresh log): start over.
+        let offset = if len < offset { 0 } else { offset };
+        let mut buf = Vec::new();
+        if len == offset
+            || file.seek(SeekFrom::Start(offset)).await.is_err()
+            || file.read_to_end(&mut buf).await.is_err()
+        {
+            // Nothing new: show a pending partial line rather than hold it.
+            if let Some(text) = self.decoder.flush_partial() {
+                tx.send(AppMessage::NodeOutput { kind, text }).ok();
+            }
+            return offset;
+        }
+
+        let mut bytes = buf.as_slice();
+        if self.skip_partial_line {
+            match bytes.iter().position(|&b| b == b'\n') {
+                Some(newline) => {
+                    bytes = &bytes[newline + 1..];
+                    self.skip_partial_line = false;
+                }
+                None => bytes = &[],
+            }
+        }
+        if let Some(text) = self.decoder.push(bytes) {
+            tx.send(AppMessage::NodeOutput { kind, text }).ok();
+        }
+        offset + buf.len() as u64
+    }
+}
@@ -0,0 +1,359 @@
+// src/options.rs
+//
+// Option catalogs, the bitcoin.conf linter and version diffs.
+//
+// After a successful build, `capture` runs `bitcoind -help -help-debug` (or
+// `electrs --help`) and stores every option (argument, default,
+// description, whether it is debug-only or deprecated) as `options.json`
+// next to the binaries.  The catalog outlives the source tree, so older
+// builds can still be compared against after their sources are cleaned up.
+//
+// `diff` compares two versions' catalogs: added, removed, changed default.
+//
+// `lint` checks a user's bitcoin.conf against one version's catalog:
+//
+//   unknown      no built version has ever had the option — likely a typo
+//   removed      an older built version had it, the target version does not
+//   deprecated   the target version's help text calls it deprecated
+//   debug-only   accepted, but only documented under `-help-debug`
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::history::unix_now;
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::process::probe;
+
+/// File name of the catalog inside a `binaries/<project>-<version>` folder.
+pub const CATALOG_FILE: &str = "options.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OptionEntry {
+    /// Without the leading dashes: `prune`, `rpcauth`.
+    pub name: String,
+    /// Argument placeholder from the help text (`<n>`), empty for flags.
+    pub arg: String,
+    pub default: Option<String>,
+    pub description: String,
+    /// Only listed by `-help-debug`.
+    #[serde(default)]
+    pub debug_only: bool,
+}
+
+impl OptionEntry {
+    /// Whether the help text calls the option deprecated (`-deprecatedrpc`
+    /// merely mentions deprecated RPCs).
+    pub fn deprecated(&self) -> bool {
+        !self.name.starts_with("deprecated")
+            && self.description.to_ascii_lowercase().contains("deprecated")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OptionCatalog {
+    /// `bitcoind` or `electrs`.
+    pub binary: String,
+    /// Tag the binary was built from.
+    pub version: String,
+    pub captured_at: u64,
+    /// Keyed by option name.
+    pub options: BTreeMap<String, OptionEntry>,
+}
+
+// ─── Capture ──────────────────────────────────────────────────────────────────
+
+/// Capture the options of `binary` (`bitcoind` or `electrs`) in
+/// `output_dir` into its catalog file.  Failure only warns — the build
+/// itself is fine.
+pub async fn capture(
+    output_dir: &Path,
+    binary: &str,
+    version: &str,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) {
+    let path = output_dir.join(binary);
+    let bin = path.to_string_lossy();
+    // Only bitcoind has a second, fuller listing of debug options.
+    let (help, full) = if binary == "bitcoind" {
+        (
+            probe(&[&bin, "-help"], env).await,
+            probe(&[&bin, "-help", "-help-debug"], env).await,
+        )
+    } else {
+        let help = probe(&[&bin, "--help"], env).await;
+        (help.clone(), help)
+    };
+    let (Some(help), Some(full)) = (help, full) else {
+        log_msg(
+            tx,
+            &format!("  ⚠  Could not capture {binary} --help; no option catalog\n"),
+        );
+        return;
+    };
+
+    let documented = parse_help(&help);
+    let mut options = parse_help(&full);
+    for (name, entry) in &mut options {
+        entry.debug_only = !documented.contains_key(name);
+    }
+    let catalog = OptionCatalog {
+        binary: binary.to_owned(),
+        version: version.to_owned(),
+        captured_at: unix_now(),
+        options,
+    };
+
+    match save(&catalog, &output_dir.join(CATALOG_FILE)).await {
+        Ok(()) => log_msg(
+            tx,
+            &format!(
+                "  ✓ Option catalog: {} options in {CATALOG_FILE}\n",
+                catalog.options.len()
+            ),
+        ),
+        Err(e) => log_msg(tx, &format!("  ⚠  {e:#}\n")),
+    }
+}
+
+async fn save(catalog: &OptionCatalog, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(catalog).context("Failed to serialise catalog")?;
+    tokio::fs::write(path, json)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn load(path: &Path) -> Result<OptionCatalog> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Every catalog under `binaries_dir`, one per built version.
+pub fn load_all(binaries_dir: &Path) -> Vec<(PathBuf, OptionCatalog)> {
+    let Ok(rd) = std::fs::read_dir(binaries_dir) else {
+        return Vec::new();
+    };
+    let mut catalogs: Vec<(PathBuf, OptionCatalog)> = rd
+        .filter_map(Result::ok)
+        .map(|e| e.path().join(CATALOG_FILE))
+        .filter_map(|p| load(&p).ok().map(|c| (p, c)))
+        .collect();
+    catalogs.sort_by(|a, b| a.0.cmp(&b.0));
+    catalogs
+}
+
+/// Parse help output: an option starts on a line whose first non-blank
+/// character is `-` and which is indented no more than a tab; the more
+/// deeply indented lines after it are its description.
+pub fn parse_help(help: &str) -> BTreeMap<String, OptionEntry> {
+    let mut options = BTreeMap::new();
+    let mut current: Option<(OptionEntry, Vec<String>)> = None;
+
+    let mut finish = |current: Option<(OptionEntry, Vec<String>)>| {
+        if let Some((mut entry, lines)) = current {
+            entry.description = lines.join(" ");
+            entry.default = extract_default(&entry.description);
+            options.insert(entry.name.clone(), entry);
+        }
+    };
+
+    for line in help.lines() {
+        let trimmed = line.trim_start();
+        let indent: usize = line[..line.len() - trimmed.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+
+        if trimmed.starts_with('-') && indent <= 4 {
+            finish(current.take());
+            let spec = trimmed.split([' ', ',', '\t']).next().unwrap_or_default();
+            let (name, arg) = spec.split_once('=').unwrap_or((spec, ""));
+            let rest = trimmed[spec.len()..].trim();
+            current = Some((
+                OptionEntry {
+                    name: name.trim_start_matches('-').to_owned(),
+                    arg: arg.to_owned(),
+                    default: None,
+                    description: String::new(),
+                    debug_only: false,
+                },
+                if rest.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![rest.to_owned()]
+                },
+            ));
+        } else if indent > 4 && !trimmed.is_empty() {
+            if let Some((_, lines)) = current.as_mut() {
+                lines.push(trimmed.to_owned());
+            }
+        } else if !trimmed.is_empty() {
+            // A section heading ends the current option.
+            finish(current.take());
+        }
+    }
+    finish(current);
+    options
+}
+
+/// The text inside `(default: …)`, nested parentheses included.
+fn extract_default(description: &str) -> Option<String> {
+    let start = description.find("(default: ")? + "(default: ".len();
+    let mut depth = 1;
+    for (i, c) in description[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(description[start..start + i].trim().to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// ─── Lint ─────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+impl LintLevel {
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Error => "✗",
+            Self::Warning => "⚠",
+            Self::Note => "ℹ",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LintFinding {
+    /// 1-based line in the conf file.
+    pub line: usize,
+    pub option: String,
+    pub level: LintLevel,
+    pub message: String,
+}
+
+/// Check the conf file `text` against `target`, using `others` (catalogs of
+/// other built versions) to tell removed options from unknown ones.
+pub fn lint(text: &str, target: &OptionCatalog, others: &[OptionCatalog]) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('[') {
+            continue;
+        }
+        let key = line.split('=').next().unwrap_or_default().trim();
+        // `main.prune=…` / `test.rpcport=…` scope an option to one chain.
+        let key = key.rsplit('.').next().unwrap_or(key);
+        let mut finding = |level, message: String| {
+            findings.push(LintFinding {
+                line: i + 1,
+                option: key.to_owned(),
+                level,
+                message,
+            });
+        };
+
+        let Some(entry) = lookup(&target.options, key) else {
+            let last_seen = others
+                .iter()
+                .filter(|c| c.version != target.version && lookup(&c.options, key).is_some())
+                .map(|c| c.version.as_str())
+                .max_by(|a, b| version_key(a).cmp(&version_key(b)));
+            match last_seen {
+                Some(v) => finding(
+                    LintLevel::Error,
+                    format!(
+                        "removed — not accepted by {} (last seen in {v})",
+                        target.version
+                    ),
+                ),
+                None => finding(
+                    LintLevel::Error,
+                    format!("unknown option for {}", target.version),
+                ),
+            }
+            continue;
+        };
+        if entry.deprecated() {
+            finding(
+                LintLevel::Warning,
+                format!("deprecated: {}", entry.description),
+            );
+        } else if entry.debug_only {
+            finding(
+                LintLevel::Note,
+                "debug-only option (listed by -help-debug only)".to_owned(),
+            );
+        }
+    }
+    findings
+}
+
+/// Look up `key`, also as the negation of a boolean (`nolisten` → `listen`).
+fn lookup<'a>(options: &'a BTreeMap<String, OptionEntry>, key: &str) -> Option<&'a OptionEntry> {
+    options
+        .get(key)
+        .or_else(|| key.strip_prefix("no").and_then(|k| options.get(k)))
+}
+
+/// Numeric sort key for a tag: `v29.0` → `[29, 0]`.
+pub fn version_key(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(|c: char| !c.is_ascii_digit())
+        .map_while(|p| p.parse().ok())
+        .collect()
+}
+
+// ─── Diff ─────────────────────────────────────────────────────────────────────
+
+/// How the options changed from one catalog to another.
+#[derive(Clone, Debug, Default)]
+pub struct OptionDiff {
+    pub added: Vec<OptionEntry>,
+    pub removed: Vec<OptionEntry>,
+    /// `(old, new)` pairs whose default differs.
+    pub changed: Vec<(OptionEntry, OptionEntry)>,
+}
+
+impl OptionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff(from: &OptionCatalog, to: &OptionCatalog) -> OptionDiff {
+    let mut result = OptionDiff::default();
+    for (name, new) in &to.options {
+        match from.options.get(name) {
+            None => result.added.push(new.clone()),
+            Some(old) if old.default != new.default => {
+                result.changed.push((old.clone(), new.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    result.removed = from
+        .options
+        .iter()
+        .filter(|(name, _)| !to.options.contains_key(*name))
+        .map(|(_, old)| old.clone())
+        .collect();
+    result
+}
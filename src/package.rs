@@ -0,0 +1,338 @@
+// src/package.rs
+//
+// Package a `binaries/<name>-<version>` folder for copying to other machines.
+//
+// The archive holds one top-level `<name>-<version>/` folder with:
+//
+//   the executables      as built (the option catalog and units stay behind)
+//   COPYING / LICENSE    from the source tree, if it has not been cleaned up
+//   MANIFEST.json        project, version, target, sizes and hashes
+//   SHA256SUMS           `sha256sum -c` format, covering everything above
+//   SHA256SUMS.asc       detached gpg signature of SHA256SUMS, when a signing
+//                        key is set (packages are unsigned otherwise)
+//
+// Archives go into `<build dir>/packages/<name>-<version>-<arch>-<os>.tar.gz`
+// (and `.zip` on request).  Archives are reproducible: tar entries use
+// `HeaderMode::Deterministic` (no owner, fixed mtime), generated files get the
+// same mtime, zip entries the zip epoch, and the manifest has no timestamp —
+// packaging the same build twice gives byte-identical archives.  Only a
+// signature differs between runs, as gpg timestamps it.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::config_gen::to_hex;
+use crate::install::{is_executable, parse_build_name};
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::signing::{detach_sign, SIGNATURE_FILE};
+
+pub const MANIFEST_FILE: &str = "MANIFEST.json";
+pub const SUMS_FILE: &str = "SHA256SUMS";
+
+/// The mtime `tar::HeaderMode::Deterministic` gives files from disk, reused
+/// for generated entries.
+const DETERMINISTIC_MTIME: u64 = 1_153_704_088;
+
+/// License files looked for at the top of the source tree.
+const LICENSE_FILES: [&str; 6] = [
+    "COPYING",
+    "LICENSE",
+    "LICENSE.md",
+    "LICENSE.txt",
+    "LICENSE-MIT",
+    "LICENSE-APACHE",
+];
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Manifest {
+    pub project: String,
+    pub version: String,
+    /// `<arch>-<os>` the binaries were built for, e.g. `x86_64-linux`.
+    pub target: String,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ManifestFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+    pub executable: bool,
+}
+
+/// A file going into the archive: copied from disk or generated.
+enum Content {
+    Path(PathBuf),
+    Data(Vec<u8>),
+}
+
+struct Entry {
+    name: String,
+    content: Content,
+    executable: bool,
+}
+
+pub fn target_triple() -> String {
+    format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
+}
+
+/// SHA-256 of a file, as lowercase hex.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// `SHA256SUMS` text for `(name, hash)` pairs, sorted by name.
+pub fn sums_text(hashes: &[(String, String)]) -> String {
+    let mut sorted: Vec<&(String, String)> = hashes.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    sorted
+        .iter()
+        .map(|(name, hash)| format!("{hash}  {name}\n"))
+        .collect()
+}
+
+/// Package `binaries_dir` into `<build dir>/packages` and return the archive
+/// paths.  With a `signing_key`, the package's `SHA256SUMS` is signed too.
+pub async fn package(
+    binaries_dir: &Path,
+    with_zip: bool,
+    signing_key: Option<String>,
+    env: HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<PathBuf>> {
+    let binaries_dir = binaries_dir.to_path_buf();
+    let tx_log = tx.clone();
+    tokio::task::spawn_blocking(move || {
+        let signer = signing_key.as_deref().map(|key| (key, &env));
+        build_archives(&binaries_dir, with_zip, signer, &tx_log)
+    })
+    .await
+    .context("Packaging task panicked")?
+}
+
+fn build_archives(
+    binaries_dir: &Path,
+    with_zip: bool,
+    signer: Option<(&str, &HashMap<String, String>)>,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<PathBuf>> {
+    let folder = binaries_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (project, version) = parse_build_name(&folder)
+        .with_context(|| format!("Not a <project>-<version> folder: {folder}"))?;
+    // `<build dir>/binaries/<folder>` → `<build dir>`.
+    let build_dir = binaries_dir
+        .parent()
+        .and_then(Path::parent)
+        .context("The binaries folder has no build directory")?;
+
+    log_msg(tx, &format!("\n📦 Packaging {folder}...\n"));
+
+    let mut entries = Vec::new();
+    let mut names: Vec<PathBuf> = std::fs::read_dir(binaries_dir)
+        .with_context(|| format!("Failed to read {}", binaries_dir.display()))?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| is_executable(p))
+        .collect();
+    names.sort();
+    if names.is_empty() {
+        anyhow::bail!("No binaries found in {}", binaries_dir.display());
+    }
+    for path in names {
+        entries.push(Entry {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            content: Content::Path(path),
+            executable: true,
+        });
+    }
+
+    let source_dir = build_dir.join(&folder);
+    let licenses: Vec<PathBuf> = LICENSE_FILES
+        .iter()
+        .map(|name| source_dir.join(name))
+        .filter(|p| p.is_file())
+        .collect();
+    if licenses.is_empty() {
+        log_msg(
+            tx,
+            &format!(
+                "  ⚠  No license file found in {} — was the source cleaned up?\n",
+                source_dir.display()
+            ),
+        );
+    }
+    for path in licenses {
+        entries.push(Entry {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            content: Content::Path(path),
+            executable: false,
+        });
+    }
+
+    let mut files = Vec::new();
+    for entry in &entries {
+        let Content::Path(path) = &entry.content else {
+            continue;
+        };
+        files.push(ManifestFile {
+            name: entry.name.clone(),
+            size: std::fs::metadata(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+                .len(),
+            sha256: sha256_file(path)?,
+            executable: entry.executable,
+        });
+    }
+    let manifest = Manifest {
+        project: project.to_owned(),
+        version: version.to_owned(),
+        target: target_triple(),
+        files,
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).context("Failed to serialise manifest")?;
+
+    let mut hashes: Vec<(String, String)> = manifest
+        .files
+        .iter()
+        .map(|f| (f.name.clone(), f.sha256.clone()))
+        .collect();
+    hashes.push((
+        MANIFEST_FILE.to_owned(),
+        to_hex(&Sha256::digest(&manifest_json)),
+    ));
+    entries.push(Entry {
+        name: MANIFEST_FILE.to_owned(),
+        content: Content::Data(manifest_json),
+        executable: false,
+    });
+    let sums = sums_text(&hashes);
+    let signature = match signer {
+        Some((key, env)) => {
+            log_msg(tx, &format!("  🔏 Signing SHA256SUMS with {key}\n"));
+            Some(detach_sign(sums.as_bytes(), key, env)?)
+        }
+        None => None,
+    };
+    entries.push(Entry {
+        name: SUMS_FILE.to_owned(),
+        content: Content::Data(sums.into_bytes()),
+        executable: false,
+    });
+    if let Some(signature) = signature {
+        entries.push(Entry {
+            name: SIGNATURE_FILE.to_owned(),
+            content: Content::Data(signature.into_bytes()),
+            executable: false,
+        });
+    }
+
+    let out_dir = build_dir.join("packages");
+    std::fs::create_dir_all(&out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let stem = format!("{folder}-{}", manifest.target);
+
+    let mut written = vec![out_dir.join(format!("{stem}.tar.gz"))];
+    write_tar_gz(&written[0], &folder, &entries)?;
+    if with_zip {
+        let path = out_dir.join(format!("{stem}.zip"));
+        write_zip(&path, &folder, &entries)?;
+        written.push(path);
+    }
+    for path in &written {
+        log_msg(
+            tx,
+            &format!(
+                "  ✓ {}\n    sha256 {}\n",
+                path.display(),
+                sha256_file(path)?
+            ),
+        );
+    }
+    Ok(written)
+}
+
+fn write_tar_gz(path: &Path, prefix: &str, entries: &[Entry]) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let gz = flate2::write::GzEncoder::new(BufWriter::new(file), flate2::Compression::best());
+    let mut tar = tar::Builder::new(gz);
+    tar.mode(tar::HeaderMode::Deterministic);
+
+    for entry in entries {
+        let name = format!("{prefix}/{}", entry.name);
+        match &entry.content {
+            Content::Path(src) => tar
+                .append_path_with_name(src, &name)
+                .with_context(|| format!("Failed to add {}", src.display()))?,
+            Content::Data(data) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(DETERMINISTIC_MTIME);
+                header.set_cksum();
+                tar.append_data(&mut header, &name, data.as_slice())
+                    .with_context(|| format!("Failed to add {name}"))?;
+            }
+        }
+    }
+    let gz = tar
+        .into_inner()
+        .context("Failed to finish the tar stream")?;
+    gz.finish()
+        .and_then(|mut w| w.flush())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn write_zip(path: &Path, prefix: &str, entries: &[Entry]) -> Result<()> {
+    use zip::write::SimpleFileOptions;
+
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    for entry in entries {
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(zip::DateTime::default())
+            .unix_permissions(if entry.executable { 0o755 } else { 0o644 });
+        let name = format!("{prefix}/{}", entry.name);
+        zip.start_file(name.as_str(), options)
+            .with_context(|| format!("Failed to add {name}"))?;
+        match &entry.content {
+            Content::Path(src) => {
+                let mut file =
+                    File::open(src).with_context(|| format!("Failed to open {}", src.display()))?;
+                std::io::copy(&mut file, &mut zip)
+                    .with_context(|| format!("Failed to add {}", src.display()))?;
+            }
+            Content::Data(data) => zip
+                .write_all(data)
+                .with_context(|| format!("Failed to add {name}"))?,
+        }
+    }
+    zip.finish()
+        .and_then(|mut w| w.flush().map_err(Into::into))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
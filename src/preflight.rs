@@ -0,0 +1,274 @@
+// src/preflight.rs
+//
+// Pre-flight checks run by `spawn_compile` before anything is cloned or
+// compiled, so that problems which would otherwise surface twenty minutes
+// into a build are reported up front:
+//
+//   disk space      free space on the build volume vs. a per-target estimate
+//   write access    the build directory can be created and written to
+//   path            no whitespace or shell / make metacharacters in the path
+//   tools           git, plus cmake (Bitcoin Core) and/or cargo (Electrs)
+//   version tags    each selected version exists as a tag upstream
+//
+// A `Fail` blocks the build; a `Warn` is logged and the build goes ahead.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::compiler::{validate_version_tag, BITCOIN_REPO, ELECTRS_REPO};
+use crate::monitor::{format_bytes, free_space};
+use crate::process::probe;
+
+/// Peak disk use of one build: a shallow clone plus build tree for Bitcoin
+/// Core; clone, `target/` (RocksDB included) and crate downloads for Electrs.
+const BITCOIN_DISK_ESTIMATE: u64 = 6_000_000_000;
+const ELECTRS_DISK_ESTIMATE: u64 = 5_000_000_000;
+
+/// Characters in the build path that break `sh -c` command lines, make
+/// rules or `PATH`-style lists.
+const HOSTILE_PATH_CHARS: &[char] = &[
+    '\'', '"', '`', '$', '\\', ';', '&', '|', '<', '>', '(', ')', '*', '?', '[', ']', '#', ':',
+    '%', '=',
+];
+
+const LS_REMOTE_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Pass => "✓",
+            Self::Warn => "⚠",
+            Self::Fail => "✗",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// What is about to be built.
+pub struct BuildPlan<'a> {
+    pub build_bitcoin: bool,
+    pub build_electrs: bool,
+    pub bitcoin_version: &'a str,
+    pub electrs_version: &'a str,
+    pub build_dir: &'a Path,
+}
+
+/// Run every check that applies to `plan`, in checklist order.
+pub async fn run_checks(plan: &BuildPlan<'_>, env: &HashMap<String, String>) -> Vec<CheckResult> {
+    let mut results = vec![
+        check_path(plan.build_dir),
+        check_writable(plan.build_dir).await,
+        check_disk_space(plan),
+        check_tool("git", env).await,
+    ];
+    if plan.build_bitcoin {
+        results.push(check_tool("cmake", env).await);
+        results.push(check_tag("Bitcoin Core", plan.bitcoin_version, BITCOIN_REPO, env).await);
+    }
+    if plan.build_electrs {
+        results.push(check_tool("cargo", env).await);
+        results.push(check_tag("Electrs", plan.electrs_version, ELECTRS_REPO, env).await);
+    }
+    results
+}
+
+pub fn has_failures(results: &[CheckResult]) -> bool {
+    results.iter().any(|r| r.status == CheckStatus::Fail)
+}
+
+/// One `✓ Name — detail` line per check.
+pub fn format_checklist(results: &[CheckResult]) -> String {
+    results
+        .iter()
+        .map(|r| format!("{} {} — {}\n", r.status.icon(), r.name, r.detail))
+        .collect()
+}
+
+// ─── Checks ───────────────────────────────────────────────────────────────────
+
+fn check_path(build_dir: &Path) -> CheckResult {
+    const NAME: &str = "Build path";
+    let path = build_dir.to_string_lossy();
+
+    if !build_dir.is_absolute() {
+        return CheckResult::new(NAME, CheckStatus::Fail, "must be an absolute path");
+    }
+    if path.chars().any(char::is_whitespace) {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            "contains whitespace, which breaks build scripts — choose a folder without spaces",
+        );
+    }
+    let hostile: String = path
+        .chars()
+        .filter(|c| HOSTILE_PATH_CHARS.contains(c))
+        .collect();
+    if !hostile.is_empty() {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("contains characters that break shell or make commands: {hostile}"),
+        );
+    }
+    if !path.is_ascii() {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Warn,
+            "contains non-ASCII characters; some build tools may mishandle them",
+        );
+    }
+    CheckResult::new(NAME, CheckStatus::Pass, path.into_owned())
+}
+
+async fn check_writable(build_dir: &Path) -> CheckResult {
+    const NAME: &str = "Write access";
+    if let Err(e) = tokio::fs::create_dir_all(build_dir).await {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("cannot create {}: {e}", build_dir.display()),
+        );
+    }
+    let probe_file = build_dir.join(".bitforge-write-test");
+    match tokio::fs::write(&probe_file, b"ok").await {
+        Ok(()) => {
+            tokio::fs::remove_file(&probe_file).await.ok();
+            CheckResult::new(NAME, CheckStatus::Pass, "build directory is writable")
+        }
+        Err(e) => CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("cannot write to {}: {e}", build_dir.display()),
+        ),
+    }
+}
+
+fn check_disk_space(plan: &BuildPlan<'_>) -> CheckResult {
+    const NAME: &str = "Disk space";
+    let mut needed = 0;
+    if plan.build_bitcoin {
+        needed += BITCOIN_DISK_ESTIMATE;
+    }
+    if plan.build_electrs {
+        needed += ELECTRS_DISK_ESTIMATE;
+    }
+
+    let Some(free) = free_space(plan.build_dir) else {
+        return CheckResult::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "could not determine free space; about {} is needed",
+                format_bytes(needed)
+            ),
+        );
+    };
+    let detail = format!(
+        "{} free, about {} needed",
+        format_bytes(free),
+        format_bytes(needed)
+    );
+    let status = if free < needed {
+        CheckStatus::Fail
+    } else if free < needed * 2 {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    CheckResult::new(NAME, status, detail)
+}
+
+async fn check_tool(tool: &str, env: &HashMap<String, String>) -> CheckResult {
+    match probe(&[tool, "--version"], env).await {
+        Some(v) => CheckResult::new(
+            tool,
+            CheckStatus::Pass,
+            v.lines().next().unwrap_or_default(),
+        ),
+        None => CheckResult::new(
+            tool,
+            CheckStatus::Fail,
+            "not found on PATH — run \"Check & Install\" in Step 1",
+        ),
+    }
+}
+
+/// Ask the upstream repository whether `refs/tags/<version>` exists.
+/// Network trouble only warns: the clone will report it properly.
+async fn check_tag(
+    project: &str,
+    version: &str,
+    repo_url: &str,
+    env: &HashMap<String, String>,
+) -> CheckResult {
+    let name = format!("{project} {version}");
+    if let Err(e) = validate_version_tag(version) {
+        return CheckResult::new(name, CheckStatus::Fail, e.to_string());
+    }
+
+    let output = tokio::time::timeout(
+        LS_REMOTE_TIMEOUT,
+        tokio::process::Command::new("git")
+            .args(["ls-remote", "--tags", repo_url])
+            .arg(format!("refs/tags/{version}"))
+            .env_clear()
+            .envs(env)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    match output {
+        Ok(Ok(out)) if out.status.success() && !out.stdout.is_empty() => {
+            CheckResult::new(name, CheckStatus::Pass, "tag exists upstream")
+        }
+        Ok(Ok(out)) if out.status.success() => CheckResult::new(
+            name,
+            CheckStatus::Fail,
+            format!("no such tag in {repo_url} — click \"Refresh\" and pick a listed release"),
+        ),
+        Ok(Ok(out)) => CheckResult::new(
+            name,
+            CheckStatus::Warn,
+            format!(
+                "could not verify the tag: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        ),
+        Ok(Err(e)) => CheckResult::new(
+            name,
+            CheckStatus::Warn,
+            format!("could not run git ls-remote: {e}"),
+        ),
+        Err(_) => CheckResult::new(
+            name,
+            CheckStatus::Warn,
+            "could not verify the tag: git ls-remote timed out",
+        ),
+    }
+}
@@ -3,35 +3,73 @@
 // `run_command`: spawn a child via `sh -c`, stream stdout+stderr to the UI.
 // `probe`:       run a command and capture its output (no logging).
 //
-// KEY DESIGN: we read stdout/stderr as raw byte chunks rather than lines.
-// This ensures that:
+// KEY DESIGN: we read stdout/stderr as raw byte chunks rather than lines,
+// and reassemble lines ourselves (`LineDecoder`).  This ensures that:
 //   • git's carriage-return-based progress ("\rReceiving 50%") is shown live.
 //   • cmake/cargo output without trailing newlines is not buffered indefinitely.
 //   • No output is ever silently swallowed in the BufReader internal buffer.
+//   • Multi-byte UTF-8 characters and \r\n pairs split across reads survive.
 
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::mpsc::Sender;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
-use crate::messages::AppMessage;
+use crate::messages::{AppMessage, LogRecord, LogStream, UiSender};
+
+/// Source of `LogRecord::command_id` — unique per `run_command` call.
+static NEXT_COMMAND_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Bytes of each stream's output kept for failure diagnosis.
+const OUTPUT_TAIL_BYTES: usize = 256 * 1024;
+
+/// A command ran but exited unsuccessfully.  Carries the tail of its output
+/// so callers can diagnose the failure after the fact; recover it from an
+/// `anyhow::Error` chain with `downcast_ref`.
+#[derive(Debug)]
+pub struct CommandFailed {
+    pub cmd: String,
+    /// Exit code, or `"signal"` when the child was killed.
+    pub code: String,
+    /// Last `OUTPUT_TAIL_BYTES` of stdout followed by those of stderr.
+    pub output: String,
+}
+
+impl std::fmt::Display for CommandFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Command failed (exit {}): {}", self.code, self.cmd)
+    }
+}
+
+impl std::error::Error for CommandFailed {}
 
 /// Execute `cmd` in a shell, streaming every byte of output to `log_tx`.
 ///
 /// * `cwd` – optional working directory for the child process.
 /// * `env` – complete environment (replaces the child's inherited env).
 ///
-/// Returns `Ok(())` on exit code 0; `Err` on non-zero exit or spawn failure.
+/// Returns `Ok(())` on exit code 0; `Err` on non-zero exit (a
+/// [`CommandFailed`] carrying the output tail) or spawn failure.
 pub async fn run_command(
     cmd: &str,
     cwd: Option<&Path>,
     env: &HashMap<String, String>,
-    log_tx: &Sender<AppMessage>,
+    log_tx: &UiSender<AppMessage>,
 ) -> Result<()> {
-    log_tx.send(AppMessage::Log(format!("\n$ {cmd}\n"))).ok();
+    let command_id = NEXT_COMMAND_ID.fetch_add(1, Ordering::Relaxed);
+    log_tx
+        .send(AppMessage::Log(LogRecord::app("\n".to_owned())))
+        .ok();
+    log_tx
+        .send(AppMessage::Log(LogRecord::new(
+            Some(command_id),
+            LogStream::Command,
+            format!("$ {cmd}\n"),
+        )))
+        .ok();
 
     let mut builder = Command::new("sh");
     builder
@@ -58,13 +96,12 @@ pub async fn run_command(
     // Drain stdout and stderr as raw byte chunks so that:
     //   - \r-terminated progress lines (git, cmake) appear immediately.
     //   - Large pipe buffers never deadlock the child process.
-    // Each chunk is sanitised: \r not followed by \n becomes \n so the
-    // terminal-style log displays correctly.
+    // Chunks are reassembled into whole lines before they are forwarded.
     let tx_out = log_tx.clone();
     let tx_err = log_tx.clone();
 
-    let stdout_task = tokio::spawn(drain_reader(stdout, tx_out));
-    let stderr_task = tokio::spawn(drain_reader(stderr, tx_err));
+    let stdout_task = tokio::spawn(drain_reader(stdout, LogStream::Stdout, command_id, tx_out));
+    let stderr_task = tokio::spawn(drain_reader(stderr, LogStream::Stderr, command_id, tx_err));
 
     // Wait for the child to exit. Because the reader tasks are independently
     // spawned and continuously draining the pipes, the child can never block
@@ -75,65 +112,169 @@ pub async fn run_command(
         .with_context(|| format!("Failed to wait for: {cmd}"))?;
 
     // Ensure every last byte is flushed before we check the exit code.
-    let _ = stdout_task.await;
-    let _ = stderr_task.await;
+    let stdout_tail = stdout_task.await.unwrap_or_default();
+    let stderr_tail = stderr_task.await.unwrap_or_default();
 
     if !status.success() {
         let code = status
             .code()
             .map_or_else(|| "signal".to_owned(), |c| c.to_string());
-        bail!("Command failed (exit {code}): {cmd}");
+        return Err(CommandFailed {
+            cmd: cmd.to_owned(),
+            code,
+            output: stdout_tail + &stderr_tail,
+        }
+        .into());
     }
 
     Ok(())
 }
 
-/// Continuously read `reader` in 8 KiB chunks and forward sanitised UTF-8
-/// text to `tx`.  Carriage returns not followed by a newline are replaced
-/// with newlines so that git/cmake progress displays properly.
-async fn drain_reader<R: AsyncReadExt + Unpin>(mut reader: R, tx: Sender<AppMessage>) {
+/// Continuously read `reader` in 8 KiB chunks and forward whole lines of
+/// UTF-8 text to `tx`, tagged with `stream` and `command_id`.  Bytes after
+/// the last line terminator are held by a [`LineDecoder`] until the next
+/// read, so neither a multi-byte character nor a `\r\n` pair is ever split.
+///
+/// Returns the last `OUTPUT_TAIL_BYTES` read, for failure diagnosis.
+async fn drain_reader<R: AsyncReadExt + Unpin>(
+    mut reader: R,
+    stream: LogStream,
+    command_id: u64,
+    tx: UiSender<AppMessage>,
+) -> String {
+    let send = |text: String| {
+        tx.send(AppMessage::Log(LogRecord::new(
+            Some(command_id),
+            stream,
+            text,
+        )))
+        .ok();
+    };
     let mut buf = vec![0u8; 8192];
-    let mut carry = Vec::new(); // bytes from last chunk that ended mid-CR/LF
+    let mut decoder = LineDecoder::default();
+    let mut tail: Vec<u8> = Vec::new();
 
     loop {
-        let n = match reader.read(&mut buf).await {
+        // While a partial line is pending, wait only briefly: a prompt or a
+        // progress bar without a terminator should still appear promptly.
+        let read = reader.read(&mut buf);
+        let result = if decoder.has_pending() {
+            match tokio::time::timeout(PARTIAL_LINE_FLUSH, read).await {
+                Ok(result) => result,
+                Err(_) => {
+                    if let Some(text) = decoder.flush_partial() {
+                        send(text);
+                    }
+                    continue;
+                }
+            }
+        } else {
+            read.await
+        };
+        let n = match result {
             Ok(0) | Err(_) => break, // EOF or error — stop reading
             Ok(n) => n,
         };
 
-        // Combine any leftover bytes with the new chunk.
-        carry.extend_from_slice(&buf[..n]);
+        tail.extend_from_slice(&buf[..n]);
+        if tail.len() > OUTPUT_TAIL_BYTES {
+            tail.drain(..tail.len() - OUTPUT_TAIL_BYTES);
+        }
+
+        if let Some(text) = decoder.push(&buf[..n]) {
+            send(text);
+        }
+    }
 
-        // Convert to a lossy UTF-8 string, replacing \r not followed by \n
-        // with \n so the log view shows each progress update on its own line.
-        let text = String::from_utf8_lossy(&carry);
-        let sanitised = sanitise_cr(text.as_ref());
+    // Flush whatever is left, complete or not.
+    if let Some(text) = decoder.finish() {
+        send(text);
+    }
 
-        // If the chunk ends mid-sequence (no trailing newline) we hold the
-        // last incomplete "line" in carry so it isn't split across chunks.
-        // For simplicity we forward everything and reset carry.
-        carry.clear();
+    String::from_utf8_lossy(&tail).into_owned()
+}
 
-        if !sanitised.is_empty() {
-            tx.send(AppMessage::Log(sanitised)).ok();
+/// How long a partial line may sit in a [`LineDecoder`] while the stream is
+/// idle before it is forwarded anyway.
+const PARTIAL_LINE_FLUSH: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// A held partial line longer than this is forwarded without waiting for its
+/// terminator, bounding memory on pathological output.
+const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+/// Streaming byte → text decoder for one pipe (or one tailed log file).
+///
+/// Only text up to the last line terminator (`\n`, or a bare `\r` used by
+/// progress output) is released; the remainder stays pending until more
+/// bytes arrive.  Terminators are ASCII, so a release point is always a
+/// UTF-8 character boundary, and a trailing `\r` is held back in case the
+/// next read starts with its `\n`.
+#[derive(Default)]
+pub struct LineDecoder {
+    pending: Vec<u8>,
+}
+
+impl LineDecoder {
+    pub const fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Add `bytes` and return every line completed so far.
+    pub fn push(&mut self, bytes: &[u8]) -> Option<String> {
+        self.pending.extend_from_slice(bytes);
+
+        // A `\r` in the last byte might be the first half of `\r\n`.
+        let searchable = match self.pending.last() {
+            Some(b'\r') => &self.pending[..self.pending.len() - 1],
+            _ => &self.pending[..],
+        };
+        match searchable.iter().rposition(|&b| b == b'\n' || b == b'\r') {
+            Some(last) => self.take(last + 1),
+            None if self.pending.len() > MAX_PENDING_BYTES => self.flush_partial(),
+            None => None,
+        }
+    }
+
+    /// Release the pending partial line up to its last complete UTF-8
+    /// character, keeping an incomplete trailing sequence (and a trailing
+    /// `\r`) for the next read.
+    pub fn flush_partial(&mut self) -> Option<String> {
+        let mut end = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            // `error_len() == None`: the input ends mid-character.
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if end > 0 && self.pending[end - 1] == b'\r' {
+            end -= 1;
         }
+        self.take(end)
+    }
+
+    /// Release everything, at end of stream.
+    pub fn finish(&mut self) -> Option<String> {
+        self.take(self.pending.len())
     }
 
-    // Flush any remaining bytes.
-    if !carry.is_empty() {
-        let text = String::from_utf8_lossy(&carry);
-        let sanitised = sanitise_cr(text.as_ref());
-        if !sanitised.is_empty() {
-            tx.send(AppMessage::Log(sanitised)).ok();
+    /// Decode and remove the first `len` pending bytes.
+    fn take(&mut self, len: usize) -> Option<String> {
+        if len == 0 {
+            return None;
         }
+        let bytes: Vec<u8> = self.pending.drain(..len).collect();
+        let text = sanitise_cr(&String::from_utf8_lossy(&bytes));
+        (!text.is_empty()).then_some(text)
     }
 }
 
-/// Normalize line endings: collapse Windows CRLF (\r\n) → \n, and strip
-/// ANSI escape sequences. Bare \r (carriage return without \n) is passed
-/// through unchanged so that `append_log` can apply true terminal semantics
-/// (overwrite the current line), keeping cmake/make progress readable
-/// instead of generating hundreds of stacked duplicate lines.
+/// Normalize line endings: collapse Windows CRLF (\r\n) → \n. Bare \r
+/// (carriage return without \n) is passed through unchanged so that
+/// `append_log` can apply true terminal semantics (overwrite the current
+/// line), keeping cmake/make progress readable instead of generating
+/// hundreds of stacked duplicate lines.
+///
+/// ANSI escape sequences are passed through too: the log panel renders SGR
+/// colours and drops every other escape (see `ansi.rs`).
 fn sanitise_cr(s: &str) -> String {
     // Fast path: nothing to do for pure ASCII with no special bytes.
     if !s.contains('\r') {
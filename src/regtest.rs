@@ -0,0 +1,460 @@
+// src/regtest.rs
+//
+// End-to-end validation of a built bitcoind / electrs pair on regtest.
+//
+//   1. start bitcoind -regtest in a temporary datadir, on free local ports
+//   2. mine MINED_BLOCKS blocks with `bitcoin-cli generatetodescriptor` to an
+//      anyone-can-spend script — no wallet needed, so wallet-less builds work
+//   3. start electrs against it (cookie auth, P2P for blocks)
+//   4. wait for electrs to index up to the tip, then query it over the
+//      Electrum protocol: `server.version` and `blockchain.headers.subscribe`
+//   5. stop both and delete the datadir
+//
+// Each step becomes one line of the report, in the same ✓ / ✗ checklist form
+// as the pre-flight checks.  Steps after the first failure are skipped.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::preflight::{CheckResult, CheckStatus};
+
+const MINED_BLOCKS: u64 = 101;
+/// `raw(51)`: an `OP_TRUE` output script.
+const MINING_DESCRIPTOR: &str = "raw(51)";
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(60);
+const INDEX_TIMEOUT: Duration = Duration::from_secs(180);
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lines of electrs' output quoted in the log when it fails.
+const LOG_TAIL_LINES: usize = 20;
+
+/// The binaries under test.
+pub struct ValidationPlan {
+    pub bitcoin_dir: PathBuf,
+    pub electrs_dir: PathBuf,
+}
+
+/// Ports for one run, all on 127.0.0.1.
+struct Ports {
+    rpc: u16,
+    p2p: u16,
+    electrum: u16,
+    monitoring: u16,
+}
+
+/// Everything started for one run, so teardown can find it.
+struct Node {
+    datadir: PathBuf,
+    ports: Ports,
+    bitcoin_cli: PathBuf,
+    bitcoind: Option<Child>,
+    electrs: Option<Child>,
+}
+
+/// Run the full validation and return the report.  Only setup problems
+/// (missing binaries, no temporary directory) are errors; a failing step
+/// is reported as a `Fail` line.
+pub async fn validate(
+    plan: &ValidationPlan,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<CheckResult>> {
+    let bitcoind = plan.bitcoin_dir.join("bitcoind");
+    let bitcoin_cli = plan.bitcoin_dir.join("bitcoin-cli");
+    let electrs = plan.electrs_dir.join("electrs");
+    for binary in [&bitcoind, &bitcoin_cli, &electrs] {
+        if !binary.is_file() {
+            anyhow::bail!("{} not found — build it first", binary.display());
+        }
+    }
+
+    let datadir = std::env::temp_dir().join(format!("bitforge-regtest-{}", std::process::id()));
+    if datadir.exists() {
+        tokio::fs::remove_dir_all(&datadir).await.ok();
+    }
+    for sub in ["bitcoin", "electrs"] {
+        tokio::fs::create_dir_all(datadir.join(sub))
+            .await
+            .with_context(|| format!("Failed to create {}", datadir.display()))?;
+    }
+
+    log_msg(
+        tx,
+        &format!("\n🧪 Regtest validation in {}\n", datadir.display()),
+    );
+    let mut node = Node {
+        datadir,
+        ports: free_ports()?,
+        bitcoin_cli,
+        bitcoind: None,
+        electrs: None,
+    };
+    let mut report = Vec::new();
+    let outcome = run_steps(&mut node, &bitcoind, &electrs, env, tx, &mut report).await;
+    if outcome.is_err() {
+        log_electrs_tail(&node, tx).await;
+    }
+    teardown(&mut node, env, tx).await;
+    Ok(report)
+}
+
+/// The steps in order, pushing one report line each; stops at the first
+/// failure.
+async fn run_steps(
+    node: &mut Node,
+    bitcoind: &Path,
+    electrs: &Path,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+    report: &mut Vec<CheckResult>,
+) -> Result<(), ()> {
+    let mut step = |name: &str, result: Result<String, String>| {
+        let (status, detail, ok) = match result {
+            Ok(detail) => (CheckStatus::Pass, detail, true),
+            Err(detail) => (CheckStatus::Fail, detail, false),
+        };
+        log_msg(tx, &format!("  {} {name} — {detail}\n", status.icon()));
+        report.push(CheckResult {
+            name: name.to_owned(),
+            status,
+            detail,
+        });
+        if ok {
+            Ok(())
+        } else {
+            Err(())
+        }
+    };
+
+    step("Start bitcoind", start_bitcoind(node, bitcoind, env).await)?;
+    step("Mine blocks", mine_blocks(node, env).await)?;
+    step("Start electrs", start_electrs(node, electrs, env))?;
+    step("Electrs indexing", wait_for_index(node).await)?;
+    step("server.version", server_version(node).await)?;
+    step(
+        "blockchain.headers.subscribe",
+        headers_subscribe(node).await,
+    )?;
+    Ok(())
+}
+
+/// Whether every step passed.
+pub fn passed(report: &[CheckResult]) -> bool {
+    !report.is_empty() && report.iter().all(|r| r.status == CheckStatus::Pass)
+}
+
+// ─── Steps ────────────────────────────────────────────────────────────────────
+
+async fn start_bitcoind(
+    node: &mut Node,
+    bitcoind: &Path,
+    env: &HashMap<String, String>,
+) -> Result<String, String> {
+    let datadir = node.datadir.join("bitcoin");
+    let child = Command::new(bitcoind)
+        .arg("-regtest")
+        .arg(format!("-datadir={}", datadir.display()))
+        .arg(format!("-rpcport={}", node.ports.rpc))
+        .arg(format!("-port={}", node.ports.p2p))
+        .args([
+            "-server=1",
+            "-listen=1",
+            "-bind=127.0.0.1",
+            "-rpcbind=127.0.0.1",
+            "-rpcallowip=127.0.0.1",
+            "-disablewallet=1",
+            "-printtoconsole=0",
+        ])
+        .env_clear()
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("cannot start {}: {e}", bitcoind.display()))?;
+    node.bitcoind = Some(child);
+
+    let count = cli(
+        node,
+        &[
+            "-rpcwait".to_owned(),
+            format!("-rpcwaittimeout={}", RPC_TIMEOUT.as_secs()),
+            "getblockcount".to_owned(),
+        ],
+        env,
+    )
+    .await?;
+    Ok(format!(
+        "RPC up on port {} (height {count})",
+        node.ports.rpc
+    ))
+}
+
+async fn mine_blocks(node: &Node, env: &HashMap<String, String>) -> Result<String, String> {
+    cli(
+        node,
+        &[
+            "generatetodescriptor".to_owned(),
+            MINED_BLOCKS.to_string(),
+            MINING_DESCRIPTOR.to_owned(),
+        ],
+        env,
+    )
+    .await?;
+    let height = cli(node, &["getblockcount".to_owned()], env).await?;
+    if height.trim() == MINED_BLOCKS.to_string() {
+        Ok(format!("height {MINED_BLOCKS}"))
+    } else {
+        Err(format!(
+            "expected height {MINED_BLOCKS}, bitcoind reports {height}"
+        ))
+    }
+}
+
+fn start_electrs(
+    node: &mut Node,
+    electrs: &Path,
+    env: &HashMap<String, String>,
+) -> Result<String, String> {
+    let local = |port: u16| format!("127.0.0.1:{port}");
+    let log = std::fs::File::create(node.datadir.join("electrs.log"))
+        .map_err(|e| format!("cannot create electrs.log: {e}"))?;
+    let log_err = log
+        .try_clone()
+        .map_err(|e| format!("cannot create electrs.log: {e}"))?;
+
+    let child = Command::new(electrs)
+        .args(["--network", "regtest", "--skip-default-conf-files"])
+        .arg("--daemon-dir")
+        .arg(node.datadir.join("bitcoin"))
+        .arg("--db-dir")
+        .arg(node.datadir.join("electrs"))
+        .args(["--daemon-rpc-addr", &local(node.ports.rpc)])
+        .args(["--daemon-p2p-addr", &local(node.ports.p2p)])
+        .args(["--electrum-rpc-addr", &local(node.ports.electrum)])
+        .args(["--monitoring-addr", &local(node.ports.monitoring)])
+        .env_clear()
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("cannot start {}: {e}", electrs.display()))?;
+    node.electrs = Some(child);
+    Ok(format!("Electrum RPC on port {}", node.ports.electrum))
+}
+
+/// Poll the tip electrs reports until it reaches the mined height.  electrs
+/// only opens its Electrum port once the initial index is done, so early
+/// polls are refused.
+async fn wait_for_index(node: &mut Node) -> Result<String, String> {
+    let started = Instant::now();
+    let mut last_error = String::from("no answer");
+    while started.elapsed() < INDEX_TIMEOUT {
+        if let Some(child) = node.electrs.as_mut() {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("electrs exited early ({status})"));
+            }
+        }
+        match electrum_call(node.ports.electrum, "blockchain.headers.subscribe", "[]").await {
+            Ok(result) if tip_height(&result) == Some(MINED_BLOCKS) => {
+                return Ok(format!(
+                    "indexed {MINED_BLOCKS} blocks in {:.0}s",
+                    started.elapsed().as_secs_f64()
+                ));
+            }
+            Ok(result) => last_error = format!("tip is {result}"),
+            Err(e) => last_error = e,
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Err(format!(
+        "not indexed after {}s ({last_error})",
+        INDEX_TIMEOUT.as_secs()
+    ))
+}
+
+async fn server_version(node: &Node) -> Result<String, String> {
+    let result = electrum_call(
+        node.ports.electrum,
+        "server.version",
+        r#"["bitforge", "1.4"]"#,
+    )
+    .await?;
+    match result.as_array().map(Vec::as_slice) {
+        Some([software, protocol]) => Ok(format!(
+            "{} (protocol {})",
+            software.as_str().unwrap_or_default(),
+            protocol.as_str().unwrap_or_default()
+        )),
+        _ => Err(format!("unexpected reply {result}")),
+    }
+}
+
+async fn headers_subscribe(node: &Node) -> Result<String, String> {
+    let result = electrum_call(node.ports.electrum, "blockchain.headers.subscribe", "[]").await?;
+    let hex_len = result
+        .get("hex")
+        .and_then(serde_json::Value::as_str)
+        .map_or(0, str::len);
+    match tip_height(&result) {
+        // An 80-byte header, hex-encoded.
+        Some(MINED_BLOCKS) if hex_len == 160 => Ok(format!("tip at height {MINED_BLOCKS}")),
+        _ => Err(format!("unexpected reply {result}")),
+    }
+}
+
+fn tip_height(result: &serde_json::Value) -> Option<u64> {
+    result.get("height").and_then(serde_json::Value::as_u64)
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Run `bitcoin-cli` against the regtest node and return its trimmed stdout.
+async fn cli(
+    node: &Node,
+    args: &[String],
+    env: &HashMap<String, String>,
+) -> Result<String, String> {
+    let out = Command::new(&node.bitcoin_cli)
+        .arg("-regtest")
+        .arg(format!(
+            "-datadir={}",
+            node.datadir.join("bitcoin").display()
+        ))
+        .arg(format!("-rpcport={}", node.ports.rpc))
+        .args(args)
+        .env_clear()
+        .envs(env)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("cannot run bitcoin-cli: {e}"))?;
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_owned())
+    } else {
+        Err(format!(
+            "bitcoin-cli {} failed: {}",
+            args.last().map_or("", String::as_str),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ))
+    }
+}
+
+/// One Electrum JSON-RPC request over a fresh connection (newline-delimited
+/// JSON over TCP); returns the `result` field.
+async fn electrum_call(port: u16, method: &str, params: &str) -> Result<serde_json::Value, String> {
+    let exchange = async {
+        let mut stream = TcpStream::connect(("127.0.0.1", port))
+            .await
+            .map_err(|e| format!("cannot connect: {e}"))?;
+        let request =
+            format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":{params}}}"#);
+        stream
+            .write_all(format!("{request}\n").as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut line = String::new();
+        BufReader::new(stream)
+            .read_line(&mut line)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut reply: serde_json::Value =
+            serde_json::from_str(&line).map_err(|e| format!("bad reply: {e}"))?;
+        if let Some(error) = reply.get("error").filter(|e| !e.is_null()) {
+            return Err(format!("{method} error: {error}"));
+        }
+        Ok(reply["result"].take())
+    };
+    tokio::time::timeout(Duration::from_secs(10), exchange)
+        .await
+        .map_err(|_| format!("{method} timed out"))?
+}
+
+/// Reserve distinct free ports by binding to port 0.  The listeners are
+/// dropped before the daemons start, which is a small race but fine for a
+/// local test.
+fn free_ports() -> Result<Ports> {
+    let listeners = (0..4)
+        .map(|_| std::net::TcpListener::bind(("127.0.0.1", 0)))
+        .collect::<std::io::Result<Vec<_>>>()
+        .context("Failed to find free local ports")?;
+    let port = |i: usize| listeners[i].local_addr().map(|a| a.port());
+    Ok(Ports {
+        rpc: port(0)?,
+        p2p: port(1)?,
+        electrum: port(2)?,
+        monitoring: port(3)?,
+    })
+}
+
+async fn log_electrs_tail(node: &Node, tx: &UiSender<AppMessage>) {
+    let Ok(log) = tokio::fs::read_to_string(node.datadir.join("electrs.log")).await else {
+        return;
+    };
+    let lines: Vec<&str> = log.lines().collect();
+    if lines.is_empty() {
+        return;
+    }
+    let tail = &lines[lines.len().saturating_sub(LOG_TAIL_LINES)..];
+    log_msg(
+        tx,
+        &format!(
+            "\n  Last lines of electrs output:\n    {}\n",
+            tail.join("\n    ")
+        ),
+    );
+}
+
+/// Stop electrs (SIGTERM) and bitcoind (`bitcoin-cli stop`), killing either
+/// if it does not exit in time, then delete the datadir.
+async fn teardown(node: &mut Node, env: &HashMap<String, String>, tx: &UiSender<AppMessage>) {
+    if let Some(child) = node.electrs.take() {
+        stop_child(child, false, env).await;
+    }
+    if let Some(child) = node.bitcoind.take() {
+        let stop_requested = cli(node, &["stop".to_owned()], env).await.is_ok();
+        stop_child(child, stop_requested, env).await;
+    }
+    match tokio::fs::remove_dir_all(&node.datadir).await {
+        Ok(()) => log_msg(tx, "  Stopped bitcoind and electrs, removed the datadir.\n"),
+        Err(e) => log_msg(
+            tx,
+            &format!("  ⚠  Could not remove {}: {e}\n", node.datadir.display()),
+        ),
+    }
+}
+
+/// Wait for `child` to exit, sending SIGTERM first unless a graceful stop
+/// was already requested; kill it after `STOP_TIMEOUT`.
+async fn stop_child(mut child: Child, stop_requested: bool, env: &HashMap<String, String>) {
+    if !stop_requested {
+        if let Some(pid) = child.id() {
+            Command::new("kill")
+                .args(["-TERM", &pid.to_string()])
+                .env_clear()
+                .envs(env)
+                .status()
+                .await
+                .ok();
+        }
+    }
+    if tokio::time::timeout(STOP_TIMEOUT, child.wait())
+        .await
+        .is_err()
+    {
+        child.kill().await.ok();
+    }
+}
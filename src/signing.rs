@@ -0,0 +1,327 @@
+// src/signing.rs
+//
+// Signed checksums for `binaries/<name>-<version>` folders.
+//
+// With a signing key configured, every successful build writes
+// `SHA256SUMS` over the files in its output folder (binaries and option
+// catalog) and signs it with
+//
+//   gpg --local-user <key> --armor --detach-sign  →  SHA256SUMS.asc
+//
+// using the local keyring, so consumers can check the binaries came from
+// this machine.  `verify` checks a folder later:
+//
+//   signature    `gpg --verify` of SHA256SUMS.asc, made by the configured
+//                signing key — a good signature from any other key fails
+//   files        every listed file exists and still has its hash
+//   unlisted     files added since signing (units, say) are only a warning
+//
+// Packages are signed the same way, with `detach_sign` (see `package.rs`).
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::package::{sha256_file, sums_text, SUMS_FILE};
+use crate::preflight::{CheckResult, CheckStatus};
+
+pub const SIGNATURE_FILE: &str = "SHA256SUMS.asc";
+
+/// Regular files directly in `dir`, except the sums and their signature.
+fn covered_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| n != SUMS_FILE && n != SIGNATURE_FILE)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Write `SHA256SUMS` for `output_dir` and sign it with `key`.
+pub async fn sign(
+    output_dir: &Path,
+    key: &str,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Result<()> {
+    log_msg(tx, &format!("\n🔏 Signing SHA256SUMS with {key}...\n"));
+    let dir = output_dir.to_path_buf();
+    let (key, env) = (key.to_owned(), env.clone());
+    let (sums, signature) = tokio::task::spawn_blocking(move || -> Result<(String, String)> {
+        let hashes = covered_files(&dir)?
+            .iter()
+            .map(|p| Ok((file_name(p), sha256_file(p)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let sums = sums_text(&hashes);
+        let signature = detach_sign(sums.as_bytes(), &key, &env)?;
+        Ok((sums, signature))
+    })
+    .await
+    .context("Signing task panicked")??;
+
+    let sums_path = output_dir.join(SUMS_FILE);
+    let sig_path = output_dir.join(SIGNATURE_FILE);
+    tokio::fs::write(&sums_path, &sums)
+        .await
+        .with_context(|| format!("Failed to write {}", sums_path.display()))?;
+    log_msg(
+        tx,
+        &format!(
+            "  ✓ {} ({} files)\n",
+            sums_path.display(),
+            sums.lines().count()
+        ),
+    );
+    tokio::fs::write(&sig_path, signature)
+        .await
+        .with_context(|| format!("Failed to write {}", sig_path.display()))?;
+    log_msg(tx, &format!("  ✓ {}\n", sig_path.display()));
+    Ok(())
+}
+
+/// Armored detached signature of `data` by `key`.  Blocking — call from
+/// `spawn_blocking`.
+pub fn detach_sign(data: &[u8], key: &str, env: &HashMap<String, String>) -> Result<String> {
+    let mut child = std::process::Command::new("gpg")
+        .args(["--batch", "--armor", "--local-user", key, "--detach-sign"])
+        .env_clear()
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("gpg not found — install GnuPG to sign builds")?;
+    // gpg reads all of its input before writing the signature, and the
+    // input is a short SHA256SUMS, so writing it first cannot deadlock.
+    child
+        .stdin
+        .take()
+        .context("gpg stdin not captured")?
+        .write_all(data)
+        .context("Failed to pass SHA256SUMS to gpg")?;
+    let output = child.wait_with_output().context("Failed to wait for gpg")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gpg could not sign with {key}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("gpg wrote a signature that is not text")
+}
+
+// ─── Verify ───────────────────────────────────────────────────────────────────
+
+/// Check `output_dir` against its `SHA256SUMS`, which must be signed by
+/// `signing_key`.
+pub async fn verify(
+    output_dir: &Path,
+    signing_key: Option<&str>,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<CheckResult>> {
+    let sums_path = output_dir.join(SUMS_FILE);
+    let sig_path = output_dir.join(SIGNATURE_FILE);
+    let sums = tokio::fs::read_to_string(&sums_path)
+        .await
+        .with_context(|| {
+            format!(
+                "{} has no {SUMS_FILE} — it was not signed",
+                output_dir.display()
+            )
+        })?;
+    log_msg(tx, &format!("\n🔍 Verifying {}...\n", output_dir.display()));
+
+    let mut report = vec![check_signature(&sums_path, &sig_path, signing_key, env).await];
+
+    let dir = output_dir.to_path_buf();
+    let file_checks = tokio::task::spawn_blocking(move || check_files(&dir, &sums))
+        .await
+        .context("Hashing task panicked")??;
+    report.extend(file_checks);
+    Ok(report)
+}
+
+async fn check_signature(
+    sums_path: &Path,
+    sig_path: &Path,
+    signing_key: Option<&str>,
+    env: &HashMap<String, String>,
+) -> CheckResult {
+    let result = |status, detail: String| CheckResult {
+        name: "Signature".to_owned(),
+        status,
+        detail,
+    };
+    if !sig_path.is_file() {
+        return result(CheckStatus::Fail, format!("{SIGNATURE_FILE} is missing"));
+    }
+    let output = tokio::process::Command::new("gpg")
+        .args(["--batch", "--status-fd", "1", "--verify"])
+        .arg(sig_path)
+        .arg(sums_path)
+        .env_clear()
+        .envs(env)
+        .output()
+        .await;
+    let Ok(output) = output else {
+        return result(CheckStatus::Fail, "gpg not found".to_owned());
+    };
+
+    // `[GNUPG:] GOODSIG <long key id> <user id>`, `[GNUPG:] VALIDSIG <fpr> …`
+    let status = String::from_utf8_lossy(&output.stdout);
+    let field = |tag: &str| {
+        status.lines().find_map(|l| {
+            l.strip_prefix("[GNUPG:] ")?
+                .strip_prefix(tag)
+                .map(|rest| rest.trim().to_owned())
+        })
+    };
+    if let (true, Some(good)) = (output.status.success(), field("GOODSIG ")) {
+        let signer = good.split_once(' ').map_or(good.as_str(), |(_, uid)| uid);
+        // `VALIDSIG <signing key fpr> … <primary key fpr>`: either may match
+        // the configured key.
+        let validsig = field("VALIDSIG ").unwrap_or_default();
+        let fields: Vec<&str> = validsig.split(' ').collect();
+        let fingerprint = fields.first().copied().unwrap_or_default();
+        let signed_by = [fingerprint, fields.get(9).copied().unwrap_or_default()];
+
+        let Some(key) = signing_key else {
+            return result(
+                CheckStatus::Fail,
+                format!("signed by {signer} {fingerprint}, but no signing key is set to trust"),
+            );
+        };
+        let trusted = key_fingerprints(key, env).await;
+        if trusted.is_empty() {
+            return result(
+                CheckStatus::Fail,
+                format!("signing key {key} is not in the keyring"),
+            );
+        }
+        if !signed_by
+            .iter()
+            .any(|f| !f.is_empty() && trusted.iter().any(|t| t.eq_ignore_ascii_case(f)))
+        {
+            return result(
+                CheckStatus::Fail,
+                format!("signed by {signer} {fingerprint}, not by the signing key {key}"),
+            );
+        }
+        return result(
+            CheckStatus::Pass,
+            format!("good signature from {signer} {fingerprint}")
+                .trim()
+                .to_owned(),
+        );
+    }
+    let detail = if let Some(bad) = field("BADSIG ") {
+        format!("BAD signature from {bad} — SHA256SUMS was modified")
+    } else if let Some(key) = field("NO_PUBKEY ") {
+        format!("signing key {key} is not in the keyring")
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        stderr
+            .lines()
+            .last()
+            .unwrap_or("gpg --verify failed")
+            .to_owned()
+    };
+    result(CheckStatus::Fail, detail)
+}
+
+/// Fingerprints of the keys (primary and subkeys) that `key` names in the
+/// keyring; empty when it names none.
+async fn key_fingerprints(key: &str, env: &HashMap<String, String>) -> Vec<String> {
+    let output = tokio::process::Command::new("gpg")
+        // `--fingerprint` twice lists the subkeys' fingerprints too.
+        .args(["--batch", "--with-colons", "--fingerprint", "--fingerprint"])
+        .arg(key)
+        .env_clear()
+        .envs(env)
+        .output()
+        .await;
+    let Some(output) = output.ok().filter(|o| o.status.success()) else {
+        return Vec::new();
+    };
+    // `fpr:::::::::<fingerprint>:`
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| l.starts_with("fpr:"))
+        .filter_map(|l| l.split(':').nth(9).map(str::to_owned))
+        .collect()
+}
+
+/// Hash every file listed in `sums` and flag files the sums do not cover.
+fn check_files(dir: &Path, sums: &str) -> Result<Vec<CheckResult>> {
+    let mut report = Vec::new();
+    let mut listed = Vec::new();
+    let mut matched = 0;
+
+    for line in sums.lines().filter(|l| !l.trim().is_empty()) {
+        // `<hash>  <name>`, or `<hash> *<name>` in binary mode.
+        let Some((expected, name)) = line.split_once(' ') else {
+            continue;
+        };
+        let name = name.trim_start_matches([' ', '*']);
+        listed.push(name.to_owned());
+
+        let path = dir.join(name);
+        let problem = if name.contains('/') || !path.is_file() {
+            Some("missing".to_owned())
+        } else {
+            let actual = sha256_file(&path)?;
+            (!actual.eq_ignore_ascii_case(expected)).then(|| format!("modified (sha256 {actual})"))
+        };
+        match problem {
+            Some(detail) => report.push(CheckResult {
+                name: name.to_owned(),
+                status: CheckStatus::Fail,
+                detail,
+            }),
+            None => matched += 1,
+        }
+    }
+    report.insert(
+        0,
+        CheckResult {
+            name: "Files".to_owned(),
+            status: if matched == listed.len() {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail
+            },
+            detail: format!("{matched} of {} listed files match", listed.len()),
+        },
+    );
+
+    let unlisted: Vec<String> = covered_files(dir)?
+        .iter()
+        .map(|p| file_name(p))
+        .filter(|n| !listed.contains(n))
+        .collect();
+    if !unlisted.is_empty() {
+        report.push(CheckResult {
+            name: "Unlisted".to_owned(),
+            status: CheckStatus::Warn,
+            detail: format!("not covered by {SUMS_FILE}: {}", unlisted.join(", ")),
+        });
+    }
+    Ok(report)
+}
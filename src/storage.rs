@@ -0,0 +1,250 @@
+// src/storage.rs
+//
+// Disk usage of the build directory, for the Storage window.
+//
+// Every build leaves behind a `bitcoin-<ver>` / `electrs-<ver>` source tree,
+// a CMake `build/` or cargo `target/` directory inside it (the bulk of the
+// space), and a `binaries/<name>-<ver>` output folder.  `scan` lists them
+// with size and last use; `plan_cleanup` picks what a cleanup action would
+// delete and how much it would free; `delete` removes it.  Folders holding
+// the binary of a running node are never offered for deletion.
+//
+// Source sizes include their artifacts, so totals are computed over the
+// selected paths with nested paths dropped — never double-counted.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::monitor::format_bytes;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    /// A `bitcoin-<ver>` / `electrs-<ver>` clone, artifacts included.
+    Source,
+    /// `bitcoin-<ver>/build`.
+    CmakeBuild,
+    /// `electrs-<ver>/target`.
+    CargoTarget,
+    /// `binaries/<name>-<ver>`.
+    Binaries,
+}
+
+impl EntryKind {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Source => "Source",
+            Self::CmakeBuild => "CMake build",
+            Self::CargoTarget => "Cargo target",
+            Self::Binaries => "Binaries",
+        }
+    }
+
+    /// Regenerated by the next build; safe to delete at any time.
+    pub const fn is_artifact(self) -> bool {
+        matches!(self, Self::CmakeBuild | Self::CargoTarget)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StorageEntry {
+    pub kind: EntryKind,
+    /// Path relative to the build directory, for display.
+    pub name: String,
+    pub path: PathBuf,
+    /// Bytes, counting every file below `path`.
+    pub size: u64,
+    /// Newest modification time below `path` (Unix seconds).
+    pub last_used: u64,
+}
+
+/// A cleanup action from the Storage window.
+#[derive(Clone, Copy, Debug)]
+pub enum Cleanup {
+    /// Every CMake `build/` and cargo `target/` directory.
+    Artifacts,
+    /// Every source tree, artifacts included.
+    Sources,
+    /// Every entry (binaries included) not used in this many days.
+    OlderThan(u64),
+}
+
+/// What a cleanup would delete.
+#[derive(Clone, Debug)]
+pub struct CleanupPlan {
+    /// Outermost selected paths; nested ones go with their parent.
+    pub paths: Vec<PathBuf>,
+    /// Space the deletion frees.
+    pub bytes: u64,
+}
+
+// ─── Scanning ─────────────────────────────────────────────────────────────────
+
+/// List the build directory's source trees, their artifact directories and
+/// the `binaries/` folders.  Blocking — call from `spawn_blocking`.
+pub fn scan(build_dir: &Path) -> Vec<StorageEntry> {
+    let mut entries = Vec::new();
+
+    for dir in subdirs(build_dir) {
+        let name = file_name(&dir);
+        let artifacts = if name.starts_with("bitcoin-") {
+            Some(("build", EntryKind::CmakeBuild))
+        } else if name.starts_with("electrs-") {
+            Some(("target", EntryKind::CargoTarget))
+        } else {
+            None
+        };
+        let Some((artifact_dir, artifact_kind)) = artifacts else {
+            continue;
+        };
+
+        entries.push(entry(EntryKind::Source, name.clone(), dir.clone()));
+        let artifact_path = dir.join(artifact_dir);
+        if artifact_path.is_dir() {
+            entries.push(entry(
+                artifact_kind,
+                format!("{name}/{artifact_dir}"),
+                artifact_path,
+            ));
+        }
+    }
+
+    for dir in subdirs(&build_dir.join("binaries")) {
+        let name = format!("binaries/{}", file_name(&dir));
+        entries.push(entry(EntryKind::Binaries, name, dir));
+    }
+
+    entries
+}
+
+fn entry(kind: EntryKind, name: String, path: PathBuf) -> StorageEntry {
+    let (size, last_used) = measure(&path);
+    StorageEntry {
+        kind,
+        name,
+        path,
+        size,
+        last_used,
+    }
+}
+
+/// Directories directly inside `dir`, sorted by name.
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = rd
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Total size and newest modification time below `path`.  Symlinks are
+/// counted as themselves, never followed.
+fn measure(path: &Path) -> (u64, u64) {
+    let mut size = 0;
+    let mut newest = 0;
+    let mut stack = vec![path.to_path_buf()];
+
+    while let Some(p) = stack.pop() {
+        let Ok(meta) = std::fs::symlink_metadata(&p) else {
+            continue;
+        };
+        if let Some(mtime) = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        {
+            newest = newest.max(mtime.as_secs());
+        }
+        if meta.is_dir() {
+            if let Ok(rd) = std::fs::read_dir(&p) {
+                stack.extend(rd.filter_map(Result::ok).map(|e| e.path()));
+            }
+        } else {
+            size += meta.len();
+        }
+    }
+
+    (size, newest)
+}
+
+// ─── Cleanup ──────────────────────────────────────────────────────────────────
+
+/// Whether deleting `entry` would remove one of the `in_use` paths (the
+/// binaries of running nodes).
+pub fn is_in_use(entry: &StorageEntry, in_use: &[PathBuf]) -> bool {
+    in_use.iter().any(|p| p.starts_with(&entry.path))
+}
+
+/// What `cleanup` would delete from the scanned `entries`, never touching
+/// the `in_use` paths.
+pub fn plan_cleanup(
+    entries: &[StorageEntry],
+    cleanup: Cleanup,
+    now: u64,
+    in_use: &[PathBuf],
+) -> CleanupPlan {
+    let selected: Vec<&StorageEntry> = entries
+        .iter()
+        .filter(|e| !is_in_use(e, in_use))
+        .filter(|e| match cleanup {
+            Cleanup::Artifacts => e.kind.is_artifact(),
+            Cleanup::Sources => e.kind == EntryKind::Source,
+            Cleanup::OlderThan(days) => e.last_used < now.saturating_sub(days * SECS_PER_DAY),
+        })
+        .collect();
+
+    let outermost: Vec<&StorageEntry> = selected
+        .iter()
+        .copied()
+        .filter(|e| {
+            !selected
+                .iter()
+                .any(|other| other.path != e.path && e.path.starts_with(&other.path))
+        })
+        .collect();
+
+    CleanupPlan {
+        paths: outermost.iter().map(|e| e.path.clone()).collect(),
+        bytes: outermost.iter().map(|e| e.size).sum(),
+    }
+}
+
+/// Delete `paths`, logging each one and the space freed (sizes come from
+/// the scanned `entries`).
+pub async fn delete(paths: Vec<PathBuf>, entries: &[StorageEntry], tx: &UiSender<AppMessage>) {
+    log_msg(tx, "\n🧹 Cleaning up build directory...\n");
+    let mut freed = 0;
+    for path in paths {
+        let size = entries
+            .iter()
+            .find(|e| e.path == path)
+            .map_or(0, |e| e.size);
+        match tokio::fs::remove_dir_all(&path).await {
+            Ok(()) => {
+                freed += size;
+                log_msg(
+                    tx,
+                    &format!("  ✓ Deleted {} ({})\n", path.display(), format_bytes(size)),
+                );
+            }
+            Err(e) => log_msg(
+                tx,
+                &format!("  ✗ Could not delete {}: {e}\n", path.display()),
+            ),
+        }
+    }
+    log_msg(tx, &format!("✓ Freed {}\n", format_bytes(freed)));
+}
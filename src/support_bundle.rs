@@ -0,0 +1,273 @@
+// src/support_bundle.rs
+//
+// "Export Diagnostics": gather everything needed to debug someone else's
+// failed build into a single `.tar.gz`:
+//
+//   build.log          the complete log of the latest build (or the terminal
+//                      contents when no log file exists yet)
+//   settings.txt       target, versions, cores, directories, build flags
+//   environment.txt    the build environment, secrets redacted
+//   dependencies.txt   Homebrew package probes
+//   tools.txt          cmake / cargo / rustc / clang / git versions
+//   cmake/             CMakeCache.txt, CMakeError.log, CMakeConfigureLog.yaml
+//                      from the Bitcoin Core source tree, when present
+//
+// Files are staged in `<build_dir>/diagnostics/<name>/` and archived with the
+// system `tar`.  The staging directory is removed afterwards, also when
+// staging or archiving fails.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::compiler::{shell_quote, BITCOIN_CMAKE_FLAGS, ELECTRS_CARGO_FLAGS};
+use crate::deps::package_versions;
+use crate::env_setup::macos_version;
+use crate::history::{format_utc_compact, unix_now};
+use crate::messages::{log_msg, AppMessage, UiSender};
+use crate::process::{probe, run_command};
+
+/// Environment variable names containing any of these are redacted.
+const SECRET_MARKERS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "AUTH",
+    "COOKIE",
+    "SESSION",
+    "PRIVATE",
+    "API_KEY",
+    "ACCESS_KEY",
+    "SIGNING_KEY",
+];
+
+/// Tool version probes written to `tools.txt`.
+const TOOL_PROBES: &[&[&str]] = &[
+    &["cmake", "--version"],
+    &["cargo", "--version"],
+    &["rustc", "--version", "--verbose"],
+    &["clang", "--version"],
+    &["git", "--version"],
+    &["pkg-config", "--version"],
+    &["uname", "-a"],
+];
+
+/// Snapshot of the UI state that goes into `settings.txt`.
+pub struct BundleSettings {
+    pub target: String,
+    pub bitcoin_version: String,
+    pub electrs_version: String,
+    pub cores: usize,
+    pub build_dir: PathBuf,
+    pub brew: Option<String>,
+    pub brew_pfx: Option<String>,
+    pub log_file: Option<PathBuf>,
+    /// Current terminal contents, used when there is no log file.
+    pub terminal_log: String,
+}
+
+/// Build the diagnostics archive and return its path.
+pub async fn export_bundle(
+    settings: BundleSettings,
+    env: HashMap<String, String>,
+    tx: UiSender<AppMessage>,
+) -> Result<PathBuf> {
+    log_msg(&tx, "\n🩺 Collecting diagnostics...\n");
+
+    let name = format!("bitforge-diagnostics-{}", format_utc_compact(unix_now()));
+    let out_dir = settings.build_dir.join("diagnostics");
+    let staging = out_dir.join(&name);
+
+    // The staging directory goes whether staging or archiving succeeded.
+    let archive = out_dir.join(format!("{name}.tar.gz"));
+    let result = match stage(&settings, &env, &staging).await {
+        Ok(()) => run_command(
+            &format!(
+                "tar -czf {} {}",
+                shell_quote(&format!("{name}.tar.gz")),
+                shell_quote(&name)
+            ),
+            Some(&out_dir),
+            &env,
+            &tx,
+        )
+        .await
+        .context("Failed to create diagnostics archive"),
+        Err(e) => Err(e),
+    };
+    tokio::fs::remove_dir_all(&staging).await.ok();
+    result?;
+
+    log_msg(
+        &tx,
+        &format!("✓ Diagnostics written to {}\n", archive.display()),
+    );
+    Ok(archive)
+}
+
+/// Write every bundle file into `staging`.
+async fn stage(
+    settings: &BundleSettings,
+    env: &HashMap<String, String>,
+    staging: &Path,
+) -> Result<()> {
+    tokio::fs::create_dir_all(staging.join("cmake"))
+        .await
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    // ── Log ───────────────────────────────────────────────────────────────────
+    let log_copied = match settings.log_file.as_deref().filter(|p| p.is_file()) {
+        Some(log) => tokio::fs::copy(log, staging.join("build.log"))
+            .await
+            .is_ok(),
+        None => false,
+    };
+    if !log_copied {
+        write(staging, "build.log", &settings.terminal_log).await?;
+    }
+
+    // ── Settings, environment, dependencies, tools ────────────────────────────
+    write(staging, "settings.txt", &settings_report(settings)).await?;
+    write(staging, "environment.txt", &environment_report(env)).await?;
+    write(
+        staging,
+        "dependencies.txt",
+        &dependencies_report(settings.brew.as_deref(), env).await,
+    )
+    .await?;
+    write(staging, "tools.txt", &tools_report(env).await).await?;
+
+    // ── CMake files ───────────────────────────────────────────────────────────
+    let version_clean = settings.bitcoin_version.trim_start_matches('v');
+    let cmake_build = settings
+        .build_dir
+        .join(format!("bitcoin-{version_clean}"))
+        .join("build");
+    for rel in [
+        "CMakeCache.txt",
+        "CMakeFiles/CMakeError.log",
+        "CMakeFiles/CMakeOutput.log",
+        "CMakeFiles/CMakeConfigureLog.yaml",
+    ] {
+        let src = cmake_build.join(rel);
+        if let (true, Some(file_name)) = (src.is_file(), src.file_name()) {
+            tokio::fs::copy(&src, staging.join("cmake").join(file_name))
+                .await
+                .with_context(|| format!("Failed to copy {}", src.display()))?;
+        }
+    }
+    Ok(())
+}
+
+async fn write(dir: &Path, name: &str, contents: &str) -> Result<()> {
+    let path = dir.join(name);
+    tokio::fs::write(&path, contents)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+// ─── Reports ──────────────────────────────────────────────────────────────────
+
+fn settings_report(s: &BundleSettings) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "BitForge {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "macOS: {}", macos_version());
+    let _ = writeln!(out, "Target: {}", s.target);
+    let _ = writeln!(out, "Bitcoin Core version: {}", s.bitcoin_version);
+    let _ = writeln!(out, "Electrs version: {}", s.electrs_version);
+    let _ = writeln!(out, "CPU cores: {}", s.cores);
+    let _ = writeln!(out, "Build directory: {}", s.build_dir.display());
+    let _ = writeln!(
+        out,
+        "Homebrew: {}",
+        s.brew.as_deref().unwrap_or("not found")
+    );
+    let _ = writeln!(
+        out,
+        "Homebrew prefix: {}",
+        s.brew_pfx.as_deref().unwrap_or("not found")
+    );
+    let _ = writeln!(
+        out,
+        "Bitcoin Core cmake flags: {}",
+        BITCOIN_CMAKE_FLAGS.join(" ")
+    );
+    let _ = writeln!(
+        out,
+        "Electrs cargo flags: {}",
+        ELECTRS_CARGO_FLAGS.join(" ")
+    );
+    out
+}
+
+/// `KEY=value` lines, sorted, with secret-looking values replaced.
+fn environment_report(env: &HashMap<String, String>) -> String {
+    let mut vars: Vec<(&String, &String)> = env.iter().collect();
+    vars.sort();
+    vars.into_iter()
+        .map(|(k, v)| {
+            let upper = k.to_ascii_uppercase();
+            if SECRET_MARKERS.iter().any(|m| upper.contains(m)) {
+                format!("{k}=<redacted>\n")
+            } else {
+                format!("{k}={}\n", redact_url_credentials(v))
+            }
+        })
+        .collect()
+}
+
+/// Replace `user:password@` in any URL inside `value` (e.g. proxy settings).
+fn redact_url_credentials(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(scheme) = rest.find("://") {
+        let (head, tail) = rest.split_at(scheme + 3);
+        out.push_str(head);
+        let authority_end = tail
+            .find(|c: char| c == '/' || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let authority = &tail[..authority_end];
+        match authority.rfind('@') {
+            Some(at) => {
+                out.push_str("<redacted>@");
+                out.push_str(&authority[at + 1..]);
+            }
+            None => out.push_str(authority),
+        }
+        rest = &tail[authority_end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+async fn dependencies_report(brew: Option<&str>, env: &HashMap<String, String>) -> String {
+    let Some(brew) = brew else {
+        return "Homebrew not found\n".to_owned();
+    };
+    let mut out = String::new();
+    if let Some(v) = probe(&[brew, "--version"], env).await {
+        let _ = writeln!(out, "{v}\n");
+    }
+    for (pkg, version) in package_versions(brew, env).await {
+        let _ = writeln!(
+            out,
+            "{pkg:<12} {}",
+            version.as_deref().unwrap_or("NOT INSTALLED")
+        );
+    }
+    out
+}
+
+async fn tools_report(env: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    for cmd in TOOL_PROBES {
+        let _ = writeln!(out, "$ {}", cmd.join(" "));
+        let result = probe(cmd, env).await;
+        let _ = writeln!(out, "{}\n", result.as_deref().unwrap_or("(not found)"));
+    }
+    out
+}
@@ -0,0 +1,253 @@
+// src/systemd.rs
+//
+// Hardened systemd units for running `bitcoind` and `electrs` on a server.
+//
+// BitForge itself runs on macOS, so the units describe the server, not this
+// machine: the bin directory, datadirs and config files are the server-side
+// paths entered in the Install window (`/usr/local/bin`, `/var/lib/bitcoind`
+// and `/var/lib/electrs` by default).  electrs is ordered after and bound to
+// bitcoind.  Each unit runs as `User=` / `Group=` with full sandboxing
+// (read-only OS, private /tmp and /dev, no privilege escalation, syscall
+// filtering) and only its datadir writable — modelled on Bitcoin Core's
+// `contrib/init/bitcoind.service`.
+//
+// The units are written next to the binaries for copying to the server.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+
+pub const BITCOIND_UNIT: &str = "bitcoind.service";
+pub const ELECTRS_UNIT: &str = "electrs.service";
+
+/// Everything the units refer to.  Paths are on the server.
+#[derive(Clone, Debug)]
+pub struct UnitSettings {
+    /// Where the server has `bitcoind` and `electrs`.
+    pub bin_dir: PathBuf,
+    /// Account the units run as.
+    pub user: String,
+    pub bitcoin_version: String,
+    pub bitcoin_datadir: PathBuf,
+    pub bitcoin_conf: Option<PathBuf>,
+    pub electrs_version: String,
+    pub electrs_db_dir: PathBuf,
+    pub electrs_conf: Option<PathBuf>,
+}
+
+/// Why `user` cannot go into `User=` / `Group=`, if it can't.  Only the
+/// portable account names `[a-z_][a-z0-9_-]*` are allowed.
+pub fn user_error(user: &str) -> Option<String> {
+    let mut chars = user.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-'));
+    (!valid).then(|| {
+        "User must start with a-z or '_' and contain only a-z, 0-9, '_' and '-'".to_owned()
+    })
+}
+
+/// Why the units cannot be generated from `s`, if they can't: systemd needs
+/// absolute paths, and an empty datadir would leave the daemon, with
+/// `ProtectSystem=strict`, nowhere to write.
+pub fn settings_error(s: &UnitSettings) -> Option<String> {
+    if let Some(error) = user_error(&s.user) {
+        return Some(error);
+    }
+    let paths = [
+        ("Bin directory", Some(&s.bin_dir)),
+        ("bitcoind datadir", Some(&s.bitcoin_datadir)),
+        ("bitcoind config", s.bitcoin_conf.as_ref()),
+        ("electrs DB dir", Some(&s.electrs_db_dir)),
+        ("electrs config", s.electrs_conf.as_ref()),
+    ];
+    paths.into_iter().find_map(|(label, path)| {
+        path.filter(|p| !p.is_absolute())
+            .map(|_| format!("{label} must be an absolute path on the server"))
+    })
+}
+
+// ─── Unit text ────────────────────────────────────────────────────────────────
+
+pub fn bitcoind_unit(s: &UnitSettings) -> String {
+    let mut exec = format!(
+        "{} {} -printtoconsole=0",
+        exec_arg(&s.bin_dir.join("bitcoind").to_string_lossy()),
+        exec_arg(&format!("-datadir={}", s.bitcoin_datadir.display()))
+    );
+    if let Some(conf) = &s.bitcoin_conf {
+        let _ = write!(exec, " {}", exec_arg(&format!("-conf={}", conf.display())));
+    }
+
+    let mut unit = format!(
+        "# Generated by BitForge for Bitcoin Core {}\n\
+         [Unit]\n\
+         Description=Bitcoin Core daemon\n\
+         Documentation=https://github.com/bitcoin/bitcoin/blob/master/doc/init.md\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exec}\n\
+         Restart=on-failure\n\
+         RestartSec=30\n\
+         # Flushing the UTXO cache on shutdown can take minutes.\n\
+         TimeoutStopSec=600\n",
+        s.bitcoin_version
+    );
+    let writable = [s.bitcoin_datadir.as_path()];
+    let readable: Vec<&Path> = s.bitcoin_conf.iter().map(PathBuf::as_path).collect();
+    push_service_tail(&mut unit, s, &writable, &readable);
+    unit
+}
+
+pub fn electrs_unit(s: &UnitSettings) -> String {
+    let mut exec = format!(
+        "{} --db-dir {}",
+        exec_arg(&s.bin_dir.join("electrs").to_string_lossy()),
+        exec_arg(&s.electrs_db_dir.to_string_lossy())
+    );
+    if let Some(conf) = &s.electrs_conf {
+        let _ = write!(exec, " --conf {}", exec_arg(&conf.to_string_lossy()));
+    }
+
+    let mut unit = format!(
+        "# Generated by BitForge for Electrs {}\n\
+         [Unit]\n\
+         Description=Electrs Electrum server\n\
+         Documentation=https://github.com/romanz/electrs/blob/master/doc/usage.md\n\
+         After={BITCOIND_UNIT}\n\
+         BindsTo={BITCOIND_UNIT}\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exec}\n\
+         Environment=RUST_BACKTRACE=1\n\
+         Restart=on-failure\n\
+         # bitcoind may still be starting or syncing; give it time.\n\
+         RestartSec=60\n\
+         TimeoutStopSec=300\n",
+        s.electrs_version
+    );
+    // electrs reads bitcoind's cookie from the bitcoind datadir.
+    let writable = [s.electrs_db_dir.as_path()];
+    let mut readable = vec![s.bitcoin_datadir.as_path()];
+    readable.extend(s.electrs_conf.as_deref());
+    push_service_tail(&mut unit, s, &writable, &readable);
+    unit
+}
+
+/// One word of a unit-file setting: `%` doubled (specifiers), and quoted
+/// with `\` and `"` escaped when it contains whitespace.
+fn quote(word: &str) -> String {
+    let word = word.replace('%', "%%");
+    if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        word
+    }
+}
+
+/// One `ExecStart=` argument: as `quote`, with `$` doubled so systemd does
+/// not expand it as a variable.
+fn exec_arg(arg: &str) -> String {
+    quote(&arg.replace('$', "$$"))
+}
+
+/// Account, sandboxing and `[Install]`, shared by both units.
+fn push_service_tail(unit: &mut String, s: &UnitSettings, writable: &[&Path], readable: &[&Path]) {
+    let join = |paths: &[&Path]| {
+        paths
+            .iter()
+            .map(|p| quote(&p.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let _ = write!(
+        unit,
+        "\n# Hardening\n\
+         NoNewPrivileges=true\n\
+         User={user}\n\
+         Group={user}\n\
+         PrivateTmp=true\n\
+         PrivateDevices=true\n\
+         ProtectSystem=strict\n\
+         ReadWritePaths={writable}\n",
+        user = s.user,
+        writable = join(writable),
+    );
+    // Datadirs under /home need the home directories visible.
+    let in_home = writable
+        .iter()
+        .chain(readable)
+        .chain([&s.bin_dir.as_path()])
+        .any(|p| p.starts_with("/home") || p.starts_with("/root"));
+    if in_home {
+        unit.push_str("ProtectHome=read-only\n");
+    } else {
+        unit.push_str("ProtectHome=true\n");
+    }
+    unit.push_str(
+        "ProtectKernelTunables=true\n\
+         ProtectKernelModules=true\n\
+         ProtectControlGroups=true\n\
+         MemoryDenyWriteExecute=true\n\
+         LockPersonality=true\n\
+         RestrictRealtime=true\n\
+         RestrictSUIDSGID=true\n\
+         RestrictAddressFamilies=AF_UNIX AF_INET AF_INET6\n\
+         SystemCallArchitectures=native\n\
+         SystemCallFilter=@system-service\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n",
+    );
+}
+
+// ─── Writing ──────────────────────────────────────────────────────────────────
+
+/// Write the units into `bitcoin_dir` / `electrs_dir` (the `binaries/`
+/// folders); a missing folder skips its unit.
+pub async fn write_units(
+    s: &UnitSettings,
+    bitcoin_dir: &Path,
+    electrs_dir: &Path,
+    tx: &UiSender<AppMessage>,
+) -> Result<Vec<PathBuf>> {
+    if let Some(error) = settings_error(s) {
+        anyhow::bail!(error);
+    }
+    let units = [
+        (bitcoin_dir, BITCOIND_UNIT, bitcoind_unit(s)),
+        (electrs_dir, ELECTRS_UNIT, electrs_unit(s)),
+    ];
+    let mut written = Vec::new();
+    for (dir, name, text) in units {
+        if !dir.is_dir() {
+            continue;
+        }
+        let path = dir.join(name);
+        tokio::fs::write(&path, text)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        log_msg(tx, &format!("  ✓ {}\n", path.display()));
+        written.push(path);
+    }
+    if written.is_empty() {
+        anyhow::bail!("Neither project has been built for the selected versions");
+    }
+    log_msg(
+        tx,
+        &format!(
+            "✓ Copy the units to /etc/systemd/system on the server, with the binaries in {}, then run:\n  systemctl daemon-reload && systemctl enable --now {BITCOIND_UNIT} {ELECTRS_UNIT}\n",
+            s.bin_dir.display()
+        ),
+    );
+    Ok(written)
+}
@@ -0,0 +1,205 @@
+// src/verify.rs
+//
+// Post-build smoke test of the copied binaries.
+//
+// A build that links but produces binaries which cannot start — a Homebrew
+// library referenced by an absolute path that has since moved, a missing
+// `@rpath` entry — would otherwise be reported as a success.  `smoke_test`
+// checks every binary in two ways:
+//
+//   libraries   every dynamic library it links against resolves
+//               (`otool -L` on macOS, `ldd` elsewhere)
+//   --version   it starts, exits cleanly and reports the version that was
+//               checked out (`v29.0` matches `v29.0.0`)
+//
+// Any failure fails the build.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::messages::{log_msg, AppMessage, UiSender};
+
+/// `--version` must not wait on anything; a hang means the binary is broken.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Run the smoke test on `binaries`, which were built from tag `version`.
+pub async fn smoke_test(
+    binaries: &[PathBuf],
+    version: &str,
+    env: &HashMap<String, String>,
+    tx: &UiSender<AppMessage>,
+) -> Result<()> {
+    log_msg(tx, "\n🔍 Verifying binaries...\n");
+    let mut failures = Vec::new();
+
+    for binary in binaries {
+        let name = binary
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let result = match unresolved_libraries(binary, env).await {
+            Ok(missing) if !missing.is_empty() => {
+                Err(format!("cannot load {}", missing.join(", ")))
+            }
+            Ok(_) => check_version(binary, version, env).await,
+            Err(e) => {
+                log_msg(
+                    tx,
+                    &format!("  ⚠  {name}: could not check libraries ({e})\n"),
+                );
+                check_version(binary, version, env).await
+            }
+        };
+        match result {
+            Ok(reported) => log_msg(tx, &format!("  ✓ {name} — {reported}\n")),
+            Err(e) => {
+                log_msg(tx, &format!("  ✗ {name} — {e}\n"));
+                failures.push(format!("{name}: {e}"));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Smoke test failed — the built binaries do not run correctly:\n{}",
+            failures.join("\n")
+        ))
+    }
+}
+
+// ─── Dynamic libraries ────────────────────────────────────────────────────────
+
+/// Libraries `binary` links against that cannot be found.  `Err` when the
+/// inspection tool itself is unavailable.
+async fn unresolved_libraries(
+    binary: &Path,
+    env: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let tool: &[&str] = if cfg!(target_os = "macos") {
+        &["otool", "-L"]
+    } else {
+        &["ldd"]
+    };
+    let out = tokio::process::Command::new(tool[0])
+        .args(&tool[1..])
+        .arg(binary)
+        .env_clear()
+        .envs(env)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("{}: {e}", tool[0]))?;
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    if cfg!(target_os = "macos") {
+        if !out.status.success() {
+            return Err(String::from_utf8_lossy(&out.stderr).trim().to_owned());
+        }
+        // First line is `<binary>:`; each following one is
+        // `\t<install name> (compatibility version …)`.
+        let exe_dir = binary.parent().unwrap_or(Path::new("."));
+        Ok(stdout
+            .lines()
+            .skip(1)
+            .filter_map(|l| l.trim().split(" (").next())
+            .filter(|lib| !lib.is_empty() && !macos_library_resolves(lib, exe_dir))
+            .map(str::to_owned)
+            .collect())
+    } else {
+        // `ldd` exits non-zero for static binaries ("not a dynamic
+        // executable"), which have nothing to resolve.
+        Ok(stdout
+            .lines()
+            .filter(|l| l.contains("=> not found"))
+            .filter_map(|l| l.split("=>").next())
+            .map(|lib| lib.trim().to_owned())
+            .collect())
+    }
+}
+
+/// Whether a Mach-O install name points at something loadable.  System
+/// libraries live in the dyld shared cache rather than on disk, and `@rpath`
+/// is resolved by dyld at launch — both are left to the `--version` run.
+fn macos_library_resolves(lib: &str, exe_dir: &Path) -> bool {
+    if lib.starts_with("/usr/lib/") || lib.starts_with("/System/") || lib.starts_with("@rpath/") {
+        return true;
+    }
+    if let Some(rest) = lib
+        .strip_prefix("@executable_path/")
+        .or_else(|| lib.strip_prefix("@loader_path/"))
+    {
+        return exe_dir.join(rest).exists();
+    }
+    Path::new(lib).exists()
+}
+
+// ─── --version ────────────────────────────────────────────────────────────────
+
+/// Run `binary --version` and return its first line if it reports `tag`.
+async fn check_version(
+    binary: &Path,
+    tag: &str,
+    env: &HashMap<String, String>,
+) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        VERSION_TIMEOUT,
+        tokio::process::Command::new(binary)
+            .arg("--version")
+            .env_clear()
+            .envs(env)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    let out = match output {
+        Ok(Ok(out)) => out,
+        Ok(Err(e)) => return Err(format!("cannot start: {e}")),
+        Err(_) => return Err(format!("--version did not exit within {VERSION_TIMEOUT:?}")),
+    };
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let reason = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(format!("--version failed ({}) {reason}", out.status)
+            .trim_end()
+            .to_owned());
+    }
+
+    let first_line = stdout.lines().next().unwrap_or_default().trim().to_owned();
+    let wanted = parse_version(tag);
+    let matches = wanted.is_some()
+        && stdout
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .any(|word| parse_version(word) == wanted);
+    if matches {
+        Ok(first_line)
+    } else {
+        Err(format!("reports \"{first_line}\", expected {tag}"))
+    }
+}
+
+/// `v29.0rc1` → `([29], "rc1")`: numeric components with trailing zeros
+/// dropped (so `29.0` and `29.0.0` compare equal) and any suffix.
+fn parse_version(s: &str) -> Option<(Vec<u64>, String)> {
+    let s = s.strip_prefix('v').unwrap_or(s);
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (numbers, suffix) = s.split_at(split);
+    let mut parts: Vec<u64> = numbers
+        .trim_end_matches('.')
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some((parts, suffix.trim_start_matches('-').to_owned()))
+}